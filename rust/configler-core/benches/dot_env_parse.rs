@@ -0,0 +1,145 @@
+use std::{fmt::Write, str::FromStr};
+
+use configler_core::sources::dot_env::DotEnvironmentConfigSource;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use regex::Regex;
+
+/// Generates a dotenv file mixing comments, unquoted, quoted and multi-line values
+fn generate_dot_env(entry_count: usize) -> String {
+    let mut dot_env_str = String::new();
+    for entry in 0..entry_count {
+        match entry % 4 {
+            0 => writeln!(dot_env_str, "# Setting {}", entry).unwrap(),
+            1 => writeln!(dot_env_str, "KEY_{}=value {} # comment", entry, entry).unwrap(),
+            2 => writeln!(
+                dot_env_str,
+                "export KEY_{}=\"quoted\\tvalue {}\"",
+                entry, entry
+            )
+            .unwrap(),
+            _ => writeln!(dot_env_str, "KEY_{}='multi\nline {}'", entry, entry).unwrap(),
+        }
+    }
+    dot_env_str
+}
+
+/// The line splitting of the regex based parser the lexer replaced, kept as a
+/// baseline. It stops at the records, so unlike `from_str` it neither interpolates
+/// nor builds the source, which makes it a lower bound of the previous parse time.
+mod regex_baseline {
+    use super::Regex;
+
+    pub fn parse_records(dot_env_str: &str) -> Vec<Result<(String, String), usize>> {
+        let assignment = Regex::new(r"^\s*(?:export\s+)?([^=]*)=(.*)$").unwrap();
+        let lines: Vec<&str> = dot_env_str
+            .split('\n')
+            .map(|line| line.strip_suffix('\r').unwrap_or(line))
+            .collect();
+
+        let mut records = Vec::new();
+        let mut index = 0;
+        while index < lines.len() {
+            let line_no = index + 1;
+            let line = lines[index];
+            index += 1;
+
+            let trimmed_line = line.trim();
+            if trimmed_line.is_empty() || trimmed_line.starts_with('#') {
+                continue;
+            }
+
+            let Some(captures) = assignment.captures(line) else {
+                records.push(Err(line_no));
+                continue;
+            };
+            let key = captures[1].trim().to_uppercase();
+            let raw_value = captures.get(2).unwrap().as_str();
+            let trimmed_value = raw_value.trim_start();
+
+            let value = match trimmed_value.chars().next() {
+                Some(quote @ ('"' | '\'' | '`')) => {
+                    parse_quoted_value(&trimmed_value[1..], quote, &lines, &mut index)
+                }
+                _ => Some(strip_inline_comment(raw_value).to_string()),
+            };
+            records.push(match value {
+                Some(value) if !key.is_empty() => Ok((key, value)),
+                _ => Err(line_no),
+            });
+        }
+        records
+    }
+
+    fn parse_quoted_value(
+        first_line: &str,
+        quote: char,
+        lines: &[&str],
+        index: &mut usize,
+    ) -> Option<String> {
+        let mut value = String::new();
+        let mut current_line = first_line;
+        loop {
+            let mut chars = current_line.char_indices();
+            while let Some((position, char)) = chars.next() {
+                if char == quote {
+                    let remainder = current_line[position + 1..].trim();
+                    return (remainder.is_empty() || remainder.starts_with('#')).then_some(value);
+                } else if char == '\\' && quote == '"' {
+                    match chars.next() {
+                        Some((_, 'n')) => value.push('\n'),
+                        Some((_, 't')) => value.push('\t'),
+                        Some((_, 'r')) => value.push('\r'),
+                        Some((_, escaped @ ('"' | '\\'))) => value.push(escaped),
+                        Some((_, other)) => {
+                            value.push('\\');
+                            value.push(other);
+                        }
+                        None => value.push('\\'),
+                    }
+                } else {
+                    value.push(char);
+                }
+            }
+
+            let next_line = lines.get(*index)?;
+            value.push('\n');
+            current_line = next_line;
+            *index += 1;
+        }
+    }
+
+    fn strip_inline_comment(raw_value: &str) -> &str {
+        let mut previous = '=';
+        for (position, char) in raw_value.char_indices() {
+            if char == '#' && previous.is_whitespace() {
+                return raw_value[..position].trim();
+            }
+            previous = char;
+        }
+        raw_value.trim()
+    }
+}
+
+fn parse_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("dot_env_parse");
+    for entry_count in [10, 1000] {
+        let dot_env_str = generate_dot_env(entry_count);
+        group.throughput(Throughput::Bytes(dot_env_str.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::new("from_str", entry_count),
+            &dot_env_str,
+            |b, dot_env_str| {
+                b.iter(|| DotEnvironmentConfigSource::from_str(black_box(dot_env_str)))
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("regex_baseline", entry_count),
+            &dot_env_str,
+            |b, dot_env_str| b.iter(|| regex_baseline::parse_records(black_box(dot_env_str))),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, parse_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,50 @@
+use std::str::FromStr;
+
+use configler_core::{
+    sources::{dot_env::DotEnvironmentConfigSource, YamlConfigSource},
+    Config, ConfigBuilder,
+};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+const PROPERTY_NAMES: [&str; 4] = [
+    "database.user",
+    "database.password",
+    "server.port",
+    "feature.flag",
+];
+
+fn build_config() -> Config {
+    let yaml_source =
+        YamlConfigSource::from_str("database:\n  user: foo\n  password: bar\n").unwrap();
+    let dot_env_source =
+        DotEnvironmentConfigSource::from_str("SERVER_PORT=8080\nFEATURE_FLAG=on\n").unwrap();
+    ConfigBuilder::new()
+        .add_custom_source(Box::new(dot_env_source))
+        .add_custom_source(Box::new(yaml_source))
+        .build()
+        .unwrap()
+}
+
+fn lookup_benchmark(c: &mut Criterion) {
+    let config = build_config();
+
+    let mut group = c.benchmark_group("config_lookup");
+    group.bench_function("get_value", |b| {
+        b.iter(|| {
+            for property_name in PROPERTY_NAMES {
+                black_box(config.get_value(black_box(property_name)));
+            }
+        })
+    });
+    group.bench_function("get_value_ref", |b| {
+        b.iter(|| {
+            for property_name in PROPERTY_NAMES {
+                black_box(config.get_value_ref(black_box(property_name)));
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, lookup_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,39 @@
+use std::{fmt::Write, str::FromStr};
+
+use configler_core::sources::{ConfigSource, YamlConfigSource};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+/// Generates a YAML document with `section_count` sections of 100 properties each
+fn generate_yaml(section_count: usize) -> String {
+    let mut yaml_str = String::new();
+    for section in 0..section_count {
+        writeln!(yaml_str, "section{}:", section).unwrap();
+        for property in 0..100 {
+            writeln!(yaml_str, "  property{}: value{}", property, property).unwrap();
+        }
+    }
+    yaml_str
+}
+
+fn lookup_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("yaml_lookup");
+    for section_count in [1, 100, 1000] {
+        let config_source = YamlConfigSource::from_str(&generate_yaml(section_count)).unwrap();
+        let property_name = format!("section{}.property50", section_count / 2);
+
+        group.bench_with_input(
+            BenchmarkId::new("get_value", section_count * 100),
+            &property_name,
+            |b, property_name| b.iter(|| config_source.get_value(black_box(property_name))),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("get_value_quoted", section_count * 100),
+            &format!("\"section{}\".property50", section_count / 2),
+            |b, property_name| b.iter(|| config_source.get_value(black_box(property_name))),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, lookup_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,316 @@
+use std::{fmt, path::Path, str::FromStr};
+
+use crate::{
+    normalize_directory, parse_locations,
+    sources::{
+        config_source::FileError, dot_env::DotEnvironmentConfigSource, ConfigSource,
+        YamlConfigSource,
+    },
+    BuildError, Config, ConfigValueError,
+};
+
+/// Environment variable naming the bootstrap file, it takes precedence over
+/// [`ConfigBuilder::set_bootstrap_file`](crate::ConfigBuilder::set_bootstrap_file)
+pub const BOOTSTRAP_FILE_VARIABLE: &str = "CONFIGLER_BOOTSTRAP_FILE";
+
+/// How configler finds and loads the sources added with
+/// [`ConfigBuilder::add_source`](crate::ConfigBuilder::add_source).
+///
+/// Settings start from the builder, are overridden by the bootstrap file and then by
+/// environment variables. The bootstrap file is a YAML (`.yaml`, `.yml`) or dotenv
+/// file holding the properties below, and environment variables use the matching
+/// environment name.
+///
+/// | Property                              | Environment variable                     | Default               |
+/// |---------------------------------------|------------------------------------------|-----------------------|
+/// | `configler.locations`                 | `CONFIGLER_LOCATIONS`                    | the working directory |
+/// | `configler.profile`                   | `CONFIGLER_PROFILE`                      | none                  |
+/// | `configler.environment.prefix`        | `CONFIGLER_ENVIRONMENT_PREFIX`           | none                  |
+/// | `configler.environment.ordinal`       | `CONFIGLER_ENVIRONMENT_ORDINAL`          | 100 (300)             |
+/// | `configler.yaml.file`                 | `CONFIGLER_YAML_FILE`                    | none                  |
+/// | `configler.yaml.optional`             | `CONFIGLER_YAML_OPTIONAL`                | false                 |
+/// | `configler.yaml.ordinal`              | `CONFIGLER_YAML_ORDINAL`                 | 265 (265)             |
+/// | `configler.dot-environment.file`      | `CONFIGLER_DOT_ENVIRONMENT_FILE`         | none                  |
+/// | `configler.dot-environment.optional`  | `CONFIGLER_DOT_ENVIRONMENT_OPTIONAL`     | false                 |
+/// | `configler.dot-environment.ordinal`   | `CONFIGLER_DOT_ENVIRONMENT_ORDINAL`      | 295 (295)             |
+///
+/// Unset ordinals are resolved when building against the builder's
+/// [`PrecedencePolicy`](crate::PrecedencePolicy), the defaults in parentheses apply
+/// to [`PrecedencePolicy::HighestOrdinalFirst`](crate::PrecedencePolicy::HighestOrdinalFirst).
+///
+/// Locations are a comma separated list of directories, each searched for
+/// `config.yaml` and the `.env` cascade. A `file` setting always names a single file
+/// which is used as-is instead of searching the locations, e.g. `/etc/app.yaml`. The
+/// dotenv cascade is looked up next to that file, so `/etc/app.env` is overridden by
+/// `/etc/app.env.local`, `/etc/app.env.{profile}` and `/etc/app.env.{profile}.local`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BootstrapSettings {
+    pub locations: Vec<String>,
+    pub profile: Option<String>,
+    /// Prefix of the environment variables read by the environment source, e.g.
+    /// `APP_` reads `database.user` from `APP_DATABASE_USER`
+    pub environment_prefix: Option<String>,
+    pub environment_ordinal: Option<usize>,
+    pub yaml: FileSourceSettings,
+    pub dot_env: FileSourceSettings,
+}
+
+/// Bootstrap settings of a file based source
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FileSourceSettings {
+    /// A file used instead of searching the locations
+    pub file: Option<String>,
+    /// Whether the build succeeds when no file is found
+    pub optional: bool,
+    pub ordinal: Option<usize>,
+}
+
+impl FileSourceSettings {
+    /// The files to load, either the configured file or `file_name` in every location
+    pub(crate) fn paths(&self, locations: &[String], file_name: &str) -> Vec<String> {
+        match &self.file {
+            Some(file) => vec![file.clone()],
+            None => locations
+                .iter()
+                .map(|location| location.to_owned() + file_name)
+                .collect(),
+        }
+    }
+}
+
+impl BootstrapSettings {
+    /// Applies the bootstrap file and then `environment` on top of these settings.
+    /// Every unreadable file and invalid value is reported.
+    pub(crate) fn resolve(
+        &self,
+        bootstrap_file: Option<&str>,
+        environment: Box<dyn ConfigSource>,
+    ) -> Result<BootstrapSettings, BuildError> {
+        let mut build_error = BuildError::default();
+        let bootstrap_file = environment
+            .get_value(BOOTSTRAP_FILE_VARIABLE)
+            .or(bootstrap_file.map(str::to_owned));
+
+        let mut sources = vec![environment];
+        if let Some(bootstrap_file) = bootstrap_file {
+            match load_bootstrap_file(&bootstrap_file) {
+                Ok(source) => sources.push(source),
+                Err(error) => build_error.errors.push(error),
+            }
+        }
+        let bootstrap = Config {
+            sources: sources.into(),
+            cache: None,
+        };
+
+        let mut settings = self.clone();
+        let read = |property_name: &str| bootstrap.get_value(property_name);
+        if let Some(locations) = read("configler.locations") {
+            settings.locations = parse_locations(&locations);
+        }
+        settings.profile = read("configler.profile").or(settings.profile);
+        settings.environment_prefix =
+            read("configler.environment.prefix").or(settings.environment_prefix);
+        settings.yaml.file = read("configler.yaml.file").or(settings.yaml.file);
+        settings.dot_env.file = read("configler.dot-environment.file").or(settings.dot_env.file);
+
+        let errors = &mut build_error.bootstrap_errors;
+        settings.environment_ordinal =
+            read_parsed(&bootstrap, "configler.environment.ordinal", errors)
+                .or(settings.environment_ordinal);
+        settings.yaml.optional = read_parsed(&bootstrap, "configler.yaml.optional", errors)
+            .unwrap_or(settings.yaml.optional);
+        settings.yaml.ordinal =
+            read_parsed(&bootstrap, "configler.yaml.ordinal", errors).or(settings.yaml.ordinal);
+        settings.dot_env.optional =
+            read_parsed(&bootstrap, "configler.dot-environment.optional", errors)
+                .unwrap_or(settings.dot_env.optional);
+        settings.dot_env.ordinal =
+            read_parsed(&bootstrap, "configler.dot-environment.ordinal", errors)
+                .or(settings.dot_env.ordinal);
+
+        settings.locations = if settings.locations.is_empty() {
+            vec![String::new()]
+        } else {
+            settings
+                .locations
+                .iter()
+                .map(|location| normalize_directory(location))
+                .collect()
+        };
+        settings.profile = settings
+            .profile
+            .filter(|profile| !profile.trim().is_empty());
+
+        if build_error.is_empty() {
+            Ok(settings)
+        } else {
+            Err(build_error)
+        }
+    }
+}
+
+/// Parses the property when it is defined, reporting values that do not parse
+fn read_parsed<T>(
+    bootstrap: &Config,
+    property_name: &str,
+    errors: &mut Vec<ConfigValueError>,
+) -> Option<T>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    match bootstrap.get_parsed_value(property_name) {
+        Ok(value) => Some(value),
+        Err(ConfigValueError::NullError { .. }) => None,
+        Err(error) => {
+            errors.push(error);
+            None
+        }
+    }
+}
+
+fn load_bootstrap_file(file_path: &str) -> Result<Box<dyn ConfigSource>, FileError> {
+    let extension = Path::new(file_path)
+        .extension()
+        .and_then(|ext| ext.to_str());
+    Ok(match extension {
+        // The bootstrap file selects the profile, so it is never profile specific itself
+        Some("yaml" | "yml") => Box::new(YamlConfigSource::from_file_without_profiles(file_path)?),
+        _ => Box::new(DotEnvironmentConfigSource::from_file(file_path)?),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Stands in for the process environment so tests do not race on real variables
+    fn environment(variables: &str) -> Box<dyn ConfigSource> {
+        Box::new(DotEnvironmentConfigSource::from_str(variables).unwrap())
+    }
+
+    #[test]
+    fn defaults_search_the_working_directory() {
+        let settings = BootstrapSettings::default()
+            .resolve(None, environment(""))
+            .unwrap();
+
+        assert_eq!(settings.locations, vec![String::new()]);
+        assert_eq!(settings.profile, None);
+        assert_eq!(settings.environment_ordinal, None);
+        assert_eq!(settings.yaml.ordinal, None);
+        assert_eq!(settings.dot_env.ordinal, None);
+        assert!(!settings.yaml.optional);
+    }
+
+    #[test]
+    fn environment_overrides_bootstrap_file_which_overrides_builder() {
+        let builder_settings = BootstrapSettings {
+            locations: vec!["builder".to_string()],
+            profile: Some("builder".to_string()),
+            ..Default::default()
+        };
+        let settings = builder_settings
+            .resolve(
+                Some("test_configs/bootstrap/configler.env"),
+                environment("CONFIGLER_PROFILE=environment"),
+            )
+            .unwrap();
+
+        assert_eq!(settings.profile, Some("environment".to_string()));
+        assert_eq!(
+            settings.locations,
+            vec![
+                "test_configs/site/".to_string(),
+                "test_configs/".to_string()
+            ]
+        );
+        assert_eq!(settings.yaml.ordinal, Some(250));
+        assert!(settings.dot_env.optional);
+    }
+
+    #[test]
+    fn bootstrap_file_can_be_yaml_and_named_by_the_environment() {
+        let settings = BootstrapSettings::default()
+            .resolve(
+                Some("test_configs/bootstrap/missing.env"),
+                environment("CONFIGLER_BOOTSTRAP_FILE=test_configs/bootstrap/configler.yaml"),
+            )
+            .unwrap();
+
+        assert_eq!(
+            settings.yaml.file,
+            Some("test_configs/site/config.yaml".to_string())
+        );
+        assert_eq!(settings.environment_prefix, Some("APP_".to_string()));
+        assert!(settings.yaml.optional);
+    }
+
+    #[test]
+    fn yaml_bootstrap_file_may_set_the_profile() {
+        let settings = BootstrapSettings::default()
+            .resolve(Some("test_configs/bootstrap/profile.yaml"), environment(""))
+            .unwrap();
+
+        assert_eq!(settings.profile, Some("prod".to_string()));
+        assert_eq!(settings.locations, vec!["test_configs/site/".to_string()]);
+    }
+
+    #[test]
+    fn yaml_bootstrap_file_may_include_the_profile() {
+        let settings = BootstrapSettings::default()
+            .resolve(
+                Some("test_configs/bootstrap/include-profile.yaml"),
+                environment(""),
+            )
+            .unwrap();
+
+        assert_eq!(settings.profile, Some("prod".to_string()));
+        assert_eq!(settings.locations, vec!["test_configs/site/".to_string()]);
+    }
+
+    #[test]
+    fn every_invalid_setting_is_reported() {
+        let build_error = BootstrapSettings::default()
+            .resolve(
+                Some("test_configs/bootstrap/missing.env"),
+                environment(
+                    "CONFIGLER_YAML_ORDINAL=high\nCONFIGLER_DOT_ENVIRONMENT_OPTIONAL=maybe",
+                ),
+            )
+            .unwrap_err();
+
+        assert_eq!(
+            build_error.errors()[0].path(),
+            "test_configs/bootstrap/missing.env"
+        );
+        let invalid_keys: Vec<&str> = build_error
+            .bootstrap_errors()
+            .iter()
+            .map(ConfigValueError::key)
+            .collect();
+        assert_eq!(
+            invalid_keys,
+            vec![
+                "configler.yaml.ordinal",
+                "configler.dot-environment.optional"
+            ]
+        );
+    }
+
+    #[test]
+    fn file_settings_are_used_as_is() {
+        let yaml = FileSourceSettings {
+            file: Some("/etc/app.yaml".to_string()),
+            ..Default::default()
+        };
+        let locations = vec!["a/".to_string(), "b/".to_string()];
+
+        assert_eq!(yaml.paths(&locations, "config.yaml"), vec!["/etc/app.yaml"]);
+        assert_eq!(
+            FileSourceSettings::default().paths(&locations, "config.yaml"),
+            vec!["a/config.yaml", "b/config.yaml"]
+        );
+    }
+}
@@ -0,0 +1,84 @@
+use std::{
+    collections::HashMap,
+    sync::{PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+
+/// Memoizes resolved values, including properties no source defines, keyed by
+/// property name. Readers share a read lock so cache hits never block each other.
+#[derive(Default)]
+pub(crate) struct ValueCache {
+    state: RwLock<CacheState>,
+}
+
+#[derive(Default)]
+struct CacheState {
+    /// Incremented on every invalidation so that values resolved before an
+    /// invalidation are never stored after it
+    generation: u64,
+    entries: HashMap<String, CachedValue>,
+}
+
+#[derive(Clone)]
+pub(crate) struct CachedValue {
+    pub(crate) value: Option<String>,
+    /// Position of the source that provided the value, `None` for misses
+    pub(crate) source_index: Option<usize>,
+}
+
+impl ValueCache {
+    /// Resolves a missing entry with `resolve` outside of the lock, storing the result
+    /// unless the cache was invalidated in the meantime
+    pub(crate) fn get_or_resolve(
+        &self,
+        property_name: &str,
+        resolve: impl FnOnce() -> CachedValue,
+    ) -> CachedValue {
+        let generation = {
+            let state = self.read_state();
+            if let Some(cached_value) = state.entries.get(property_name) {
+                return cached_value.clone();
+            }
+            state.generation
+        };
+
+        let resolved_value = resolve();
+        let mut state = self.write_state();
+        if state.generation == generation {
+            state
+                .entries
+                .insert(property_name.to_owned(), resolved_value.clone());
+        }
+        resolved_value
+    }
+
+    pub(crate) fn clear(&self) {
+        let mut state = self.write_state();
+        state.generation += 1;
+        state.entries.clear();
+    }
+
+    /// Removes every entry the source at `source_index` could change: values it
+    /// provided, values from sources it takes precedence over, and misses
+    pub(crate) fn invalidate_from(&self, source_index: usize) {
+        let mut state = self.write_state();
+        state.generation += 1;
+        state.entries.retain(|_, cached_value| {
+            cached_value
+                .source_index
+                .is_some_and(|index| index < source_index)
+        });
+    }
+
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.read_state().entries.len()
+    }
+
+    fn read_state(&self) -> RwLockReadGuard<'_, CacheState> {
+        self.state.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn write_state(&self) -> RwLockWriteGuard<'_, CacheState> {
+        self.state.write().unwrap_or_else(PoisonError::into_inner)
+    }
+}
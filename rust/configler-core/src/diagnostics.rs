@@ -0,0 +1,239 @@
+use std::{collections::HashMap, fmt::Write, fs};
+
+use crate::sources::config_source::FileError;
+
+/// A problem in a configuration file, which can be rendered with a frame of the
+/// offending lines
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub file_name: String,
+    /// 1-based line and column, when the problem has a position
+    pub location: Option<(usize, usize)>,
+    pub message: String,
+    pub help: Option<&'static str>,
+}
+
+impl Diagnostic {
+    /// Renders the diagnostic using `source`, the content of the file, for the code frame:
+    ///
+    /// ```text
+    /// error: value is empty
+    ///  --> .env:3:15
+    ///   |
+    /// 2 | DATABASE_PASSWORD=secret
+    /// 3 | DATABASE_HOST=
+    ///   |               ^
+    ///   = help: set a value, or allow empty values with an EmptyValuePolicy
+    /// ```
+    pub fn render(&self, source: Option<&str>) -> String {
+        let frame = match (self.location, source) {
+            (Some((line, _)), Some(source)) => code_frame(source, line),
+            _ => Vec::new(),
+        };
+        let gutter = " ".repeat(
+            frame
+                .last()
+                .map_or(1, |(line_number, _)| line_number.to_string().len()),
+        );
+
+        let mut rendered = format!("error: {}\n", self.message);
+        let location = match self.location {
+            Some((line, column)) => format!("{}:{}:{}", self.file_name, line, column),
+            None => self.file_name.clone(),
+        };
+        writeln!(rendered, "{}--> {}", gutter, location).unwrap();
+        if let (Some((_, column)), Some((_, line_text))) = (self.location, frame.last()) {
+            writeln!(rendered, "{} |", gutter).unwrap();
+            for (line_number, text) in &frame {
+                let width = gutter.len();
+                writeln!(rendered, "{:>width$} | {}", line_number, text).unwrap();
+            }
+            let padding = caret_padding(line_text, column);
+            writeln!(rendered, "{} | {}^", gutter, padding).unwrap();
+        }
+        if let Some(help) = self.help {
+            writeln!(rendered, "{} = help: {}", gutter, help).unwrap();
+        }
+        rendered
+    }
+}
+
+/// The offending line preceded by one line of context, or nothing when `line` is
+/// past the end of `source`
+fn code_frame(source: &str, line: usize) -> Vec<(usize, &str)> {
+    let first_line = line.saturating_sub(1).max(1);
+    let frame: Vec<(usize, &str)> = source
+        .lines()
+        .enumerate()
+        .map(|(index, text)| (index + 1, text))
+        .skip(first_line - 1)
+        .take((line + 1).saturating_sub(first_line))
+        .collect();
+    match frame.last() {
+        Some((last_line, _)) if *last_line == line => frame,
+        _ => Vec::new(),
+    }
+}
+
+/// Whitespace up to `column` that keeps the caret aligned when the line holds tabs
+fn caret_padding(line_text: &str, column: usize) -> String {
+    line_text
+        .chars()
+        .take(column.saturating_sub(1))
+        .map(|char| if char == '\t' { '\t' } else { ' ' })
+        .collect()
+}
+
+impl FileError {
+    /// Diagnostics for every problem behind this error
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        match self {
+            FileError::DotEnvLineParseErrors { path, errors } => errors.diagnostics(path),
+            FileError::YamlParseError { path, error } => error.diagnostics(path),
+            FileError::IoError { path, error } => vec![Diagnostic {
+                file_name: path.clone(),
+                location: None,
+                message: error.to_string(),
+                help: None,
+            }],
+        }
+    }
+
+    /// Renders every diagnostic with a code frame. The files are read again to build
+    /// the frames, so frames are left out for files that can no longer be read.
+    pub fn render_diagnostics(&self) -> String {
+        let mut sources: HashMap<String, Option<String>> = HashMap::new();
+        self.diagnostics()
+            .iter()
+            .map(|diagnostic| {
+                let source = sources
+                    .entry(diagnostic.file_name.clone())
+                    .or_insert_with(|| match diagnostic.location {
+                        Some(_) => fs::read_to_string(&diagnostic.file_name).ok(),
+                        None => None,
+                    });
+                diagnostic.render(source.as_deref())
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::{
+        sources::{
+            dot_env::DotEnvironmentConfigSource,
+            yaml::{YamlConfigSource, YamlParseOptions},
+            ConfigSource,
+        },
+        ConfigBuilder, SourceName,
+    };
+
+    #[test]
+    fn renders_dot_env_errors_with_a_code_frame() {
+        let mut builder = ConfigBuilder::new();
+        builder
+            .add_source(SourceName::DotEnvironmentFile)
+            .set_config_directory("test_configs/errors");
+        let rendered = builder.build().err().unwrap().render_diagnostics();
+
+        assert_eq!(
+            rendered,
+            "error: Record has invalid '=' operand
+ --> test_configs/errors/.env:2:1
+  |
+1 | DATABASE_USER=foo
+2 | DATABASE_PASSWORD
+  | ^
+  = help: assignments are written as KEY=value
+
+error: value is empty
+ --> test_configs/errors/.env:3:15
+  |
+2 | DATABASE_PASSWORD
+3 | DATABASE_HOST=
+  |               ^
+  = help: set a value, or allow empty values with an EmptyValuePolicy
+"
+        );
+    }
+
+    #[test]
+    fn renders_yaml_validation_errors() {
+        let yaml_str = "database:\n  user: foo\n  user: bar\n";
+        let options = YamlParseOptions {
+            strict: true,
+            ..Default::default()
+        };
+        let error = YamlConfigSource::from_str_with_options(yaml_str, &options)
+            .err()
+            .unwrap();
+        let diagnostics = error.diagnostics("config.yaml");
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].render(Some(yaml_str)),
+            "error: Duplicate key 'user'
+ --> config.yaml:3:3
+  |
+2 |   user: foo
+3 |   user: bar
+  |   ^
+  = help: remove one of the duplicate keys
+"
+        );
+    }
+
+    #[test]
+    fn renders_yaml_syntax_errors() {
+        let yaml_str = "database:\n  user: [foo\n";
+        let error = YamlConfigSource::from_str(yaml_str).err().unwrap();
+        let rendered = error.diagnostics("config.yaml")[0].render(Some(yaml_str));
+
+        assert!(rendered.starts_with("error: "));
+        assert!(rendered.contains(" --> config.yaml:"));
+        assert!(rendered.contains("= help: check the indentation"));
+    }
+
+    #[test]
+    fn included_file_errors_point_at_the_included_file() {
+        let error = YamlConfigSource::from_file("test_configs/includes/cycle/a.yaml")
+            .err()
+            .unwrap();
+        let diagnostics = error.diagnostics();
+
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].file_name.ends_with("a.yaml"));
+        assert!(diagnostics[0].location.is_none());
+        assert!(error
+            .render_diagnostics()
+            .contains("= help: remove the include that leads back to this file"));
+    }
+
+    #[test]
+    fn io_errors_render_without_a_frame() {
+        let error = DotEnvironmentConfigSource::from_file("test_configs/missing/.env")
+            .err()
+            .unwrap();
+        let rendered = error.render_diagnostics();
+
+        assert!(rendered.starts_with("error: "));
+        assert!(rendered.ends_with(" --> test_configs/missing/.env\n"));
+    }
+
+    #[test]
+    fn caret_follows_tabs_and_multi_line_values() {
+        let dot_env_str = "A='first\nsecond' trailing\n";
+        let error = DotEnvironmentConfigSource::from_str(dot_env_str)
+            .err()
+            .unwrap();
+        let rendered = error.diagnostics(".env")[0].render(Some(dot_env_str));
+        assert!(rendered.contains("2 | second' trailing\n  |         ^\n"));
+
+        assert_eq!(caret_padding("\tKEY = 'value", 8), "\t      ");
+    }
+}
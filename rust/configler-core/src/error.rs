@@ -0,0 +1,333 @@
+use core::fmt;
+use std::error::Error;
+
+use crate::{
+    global::GlobalConfigError,
+    sources::{
+        config_source::FileError, dot_env::DotEnvLineParseErrors, property_path::PropertyPathError,
+        yaml::YamlParseError,
+    },
+};
+
+/// Any error returned by configler, so that callers can propagate every error with `?`.
+/// Displays and chains its sources like the error it wraps.
+#[derive(Debug)]
+pub enum ConfigError {
+    Build(BuildError),
+    File(FileError),
+    /// Dotenv content parsed from a string
+    DotEnvParse(DotEnvLineParseErrors),
+    /// YAML content parsed from a string
+    YamlParse(YamlParseError),
+    PropertyPath(PropertyPathError),
+    Value(ConfigValueError),
+    Global(GlobalConfigError),
+}
+
+impl ConfigError {
+    fn inner(&self) -> &(dyn Error + 'static) {
+        match self {
+            ConfigError::Build(error) => error,
+            ConfigError::File(error) => error,
+            ConfigError::DotEnvParse(error) => error,
+            ConfigError::YamlParse(error) => error,
+            ConfigError::PropertyPath(error) => error,
+            ConfigError::Value(error) => error,
+            ConfigError::Global(error) => error,
+        }
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.inner())
+    }
+}
+
+impl Error for ConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.inner().source()
+    }
+}
+
+impl From<BuildError> for ConfigError {
+    fn from(error: BuildError) -> Self {
+        ConfigError::Build(error)
+    }
+}
+
+impl From<FileError> for ConfigError {
+    fn from(error: FileError) -> Self {
+        ConfigError::File(error)
+    }
+}
+
+impl From<DotEnvLineParseErrors> for ConfigError {
+    fn from(error: DotEnvLineParseErrors) -> Self {
+        ConfigError::DotEnvParse(error)
+    }
+}
+
+impl From<YamlParseError> for ConfigError {
+    fn from(error: YamlParseError) -> Self {
+        ConfigError::YamlParse(error)
+    }
+}
+
+impl From<PropertyPathError> for ConfigError {
+    fn from(error: PropertyPathError) -> Self {
+        ConfigError::PropertyPath(error)
+    }
+}
+
+impl From<ConfigValueError> for ConfigError {
+    fn from(error: ConfigValueError) -> Self {
+        ConfigError::Value(error)
+    }
+}
+
+impl From<GlobalConfigError> for ConfigError {
+    fn from(error: GlobalConfigError) -> Self {
+        ConfigError::Global(error)
+    }
+}
+
+/// Every source that failed to load while building a config, along with any invalid
+/// bootstrap settings
+#[derive(Debug, Default)]
+pub struct BuildError {
+    pub(crate) errors: Vec<FileError>,
+    pub(crate) bootstrap_errors: Vec<ConfigValueError>,
+}
+
+impl BuildError {
+    /// The source failures in the order the sources were loaded
+    pub fn errors(&self) -> &[FileError] {
+        &self.errors
+    }
+
+    /// Bootstrap settings that could not be parsed, keyed by their property name
+    pub fn bootstrap_errors(&self) -> &[ConfigValueError] {
+        &self.bootstrap_errors
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.errors.is_empty() && self.bootstrap_errors.is_empty()
+    }
+
+    /// Renders the diagnostics of every failing source
+    #[cfg(feature = "diagnostics")]
+    pub fn render_diagnostics(&self) -> String {
+        self.errors
+            .iter()
+            .map(FileError::render_diagnostics)
+            .chain(
+                self.bootstrap_errors
+                    .iter()
+                    .map(|error| format!("error: {}\n", error)),
+            )
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Failed to build the config, found {} error(s):",
+            self.errors.len() + self.bootstrap_errors.len()
+        )?;
+        let messages = self.errors.iter().map(ToString::to_string).chain(
+            self.bootstrap_errors
+                .iter()
+                .map(|error| format!("Invalid bootstrap setting: {}", error)),
+        );
+        for message in messages {
+            // Nested lines are indented so that each failure stands out
+            write!(f, "\n- {}", message.trim_end().replace('\n', "\n  "))?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for BuildError {
+    /// The first failure, use [`BuildError::errors`] for the others
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self.errors.first() {
+            Some(error) => Some(error),
+            None => self
+                .bootstrap_errors
+                .first()
+                .map(|error| error as &(dyn Error + 'static)),
+        }
+    }
+}
+
+/// A property value that is missing or cannot be converted, along with its key
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigValueError {
+    TypeError {
+        key: String,
+        value: String,
+        expected_type: &'static str,
+        message: String,
+    },
+    NullError {
+        key: String,
+    },
+}
+
+impl ConfigValueError {
+    /// The property the value belongs to
+    pub fn key(&self) -> &str {
+        match self {
+            ConfigValueError::TypeError { key, .. } | ConfigValueError::NullError { key } => key,
+        }
+    }
+}
+
+impl fmt::Display for ConfigValueError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigValueError::TypeError {
+                key,
+                value,
+                expected_type,
+                message,
+            } => write!(
+                f,
+                "Property '{}' has value '{}' which is not a valid {}: {}",
+                key, value, expected_type, message
+            ),
+            ConfigValueError::NullError { key } => {
+                write!(f, "Property '{}' is not defined", key)
+            }
+        }
+    }
+}
+
+impl Error for ConfigValueError {}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::{
+        sources::{ConfigSource, DropInDirectory, PropertyPath, YamlConfigSource},
+        ConfigBuilder,
+    };
+
+    fn assert_error<T: Error + Send + Sync + 'static>() {}
+
+    #[test]
+    fn errors_are_thread_safe_std_errors() {
+        assert_error::<ConfigError>();
+        assert_error::<BuildError>();
+        assert_error::<FileError>();
+        assert_error::<DotEnvLineParseErrors>();
+        assert_error::<YamlParseError>();
+        assert_error::<PropertyPathError>();
+        assert_error::<ConfigValueError>();
+        assert_error::<GlobalConfigError>();
+    }
+
+    #[test]
+    fn file_errors_name_the_file_and_chain_the_cause() {
+        let file_error = YamlConfigSource::from_file("test_configs/missing/config.yaml")
+            .err()
+            .unwrap();
+        let error = ConfigError::from(file_error);
+
+        assert!(matches!(
+            &error,
+            ConfigError::File(file_error) if file_error.path() == "test_configs/missing/config.yaml"
+        ));
+        assert!(error
+            .to_string()
+            .starts_with("test_configs/missing/config.yaml: "));
+        let io_error = error.source().unwrap().downcast_ref::<std::io::Error>();
+        assert_eq!(
+            io_error.map(std::io::Error::kind),
+            Some(std::io::ErrorKind::NotFound)
+        );
+
+        let drop_in_error = DropInDirectory::new("test_configs/missing.d")
+            .load()
+            .err()
+            .unwrap();
+        assert_eq!(drop_in_error.path(), "test_configs/missing.d");
+    }
+
+    #[test]
+    fn parse_errors_are_chained_through_file_errors() {
+        let mut builder = ConfigBuilder::new();
+        builder
+            .add_source(crate::SourceName::DotEnvironmentFile)
+            .set_config_directory("test_configs/errors");
+        let error = ConfigError::from(builder.build().err().unwrap());
+
+        assert!(error.to_string().starts_with(
+            "Failed to build the config, found 1 error(s):\n- Failed to parse 'test_configs/errors/.env'"
+        ));
+        let file_error = error.source().unwrap();
+        assert!(file_error.downcast_ref::<FileError>().is_some());
+        assert!(file_error
+            .source()
+            .unwrap()
+            .downcast_ref::<DotEnvLineParseErrors>()
+            .is_some());
+    }
+
+    #[test]
+    fn value_errors_include_the_key() {
+        let config = ConfigBuilder::new()
+            .add_custom_source(Box::new(
+                YamlConfigSource::from_str("server:\n  port: http\n").unwrap(),
+            ))
+            .build()
+            .unwrap();
+
+        let missing = config.get_required_value("server.host").unwrap_err();
+        assert_eq!(missing.key(), "server.host");
+        assert_eq!(missing.to_string(), "Property 'server.host' is not defined");
+
+        let invalid = config.get_parsed_value::<u16>("server.port").unwrap_err();
+        assert_eq!(invalid.key(), "server.port");
+        assert!(matches!(
+            invalid,
+            ConfigValueError::TypeError { ref value, expected_type: "u16", .. } if value == "http"
+        ));
+    }
+
+    #[test]
+    fn question_mark_converts_every_error() {
+        fn load_port(yaml_str: &str) -> Result<u16, ConfigError> {
+            let source = YamlConfigSource::from_str(yaml_str)?;
+            let property_path = PropertyPath::from_str("server.port")?;
+            let config = ConfigBuilder::new()
+                .add_custom_source(Box::new(source))
+                .build()?;
+            Ok(config.get_parsed_value(&property_path.to_string())?)
+        }
+
+        fn load_port_boxed(yaml_str: &str) -> Result<u16, Box<dyn Error + Send + Sync>> {
+            Ok(load_port(yaml_str)?)
+        }
+
+        assert_eq!(load_port("server:\n  port: 8080\n").unwrap(), 8080);
+        assert!(matches!(
+            load_port("server:\n  port: [8080\n"),
+            Err(ConfigError::YamlParse(_))
+        ));
+        assert!(matches!(
+            load_port("server:\n  host: localhost\n"),
+            Err(ConfigError::Value(ConfigValueError::NullError { .. }))
+        ));
+        assert!(load_port_boxed("server:\n  port: -1\n")
+            .unwrap_err()
+            .downcast_ref::<ConfigError>()
+            .is_some());
+    }
+}
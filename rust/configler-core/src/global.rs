@@ -0,0 +1,167 @@
+use core::fmt;
+#[cfg(any(test, feature = "testing"))]
+use std::sync::RwLock;
+use std::{error::Error, sync::OnceLock};
+
+use crate::{BuildError, Config, ConfigBuilder};
+
+/// The process wide config, set once by [`init`]
+static GLOBAL_CONFIG: OnceLock<Config> = OnceLock::new();
+
+/// Test-only replacement for [`GLOBAL_CONFIG`], which cannot be cleared
+#[cfg(any(test, feature = "testing"))]
+enum TestConfig {
+    /// The global config is used as is
+    Unused,
+    /// The config was reset and behaves as if [`init`] was never called
+    Reset,
+    /// Replaced configs are leaked so references handed out stay valid
+    Config(&'static Config),
+}
+
+#[cfg(any(test, feature = "testing"))]
+static TEST_CONFIG: RwLock<TestConfig> = RwLock::new(TestConfig::Unused);
+
+/// Builds the process wide config, which can then be read anywhere with [`config`].
+/// The global config can only be initialized once.
+pub fn init(builder: &ConfigBuilder) -> Result<(), GlobalConfigError> {
+    #[cfg(any(test, feature = "testing"))]
+    {
+        let mut test_config = TEST_CONFIG
+            .write()
+            .unwrap_or_else(|error| error.into_inner());
+        match *test_config {
+            TestConfig::Unused => {}
+            TestConfig::Reset => {
+                let config = builder.build().map_err(GlobalConfigError::BuildError)?;
+                *test_config = TestConfig::Config(Box::leak(Box::new(config)));
+                return Ok(());
+            }
+            TestConfig::Config(_) => return Err(GlobalConfigError::AlreadyInitialized),
+        }
+    }
+
+    if GLOBAL_CONFIG.get().is_some() {
+        return Err(GlobalConfigError::AlreadyInitialized);
+    }
+    let config = builder.build().map_err(GlobalConfigError::BuildError)?;
+    GLOBAL_CONFIG
+        .set(config)
+        .map_err(|_| GlobalConfigError::AlreadyInitialized)
+}
+
+/// Returns the process wide config, which lives for the rest of the program so
+/// property groups borrowing it can be stored anywhere
+pub fn config() -> Result<&'static Config, GlobalConfigError> {
+    #[cfg(any(test, feature = "testing"))]
+    match *TEST_CONFIG
+        .read()
+        .unwrap_or_else(|error| error.into_inner())
+    {
+        TestConfig::Unused => {}
+        TestConfig::Reset => return Err(GlobalConfigError::NotInitialized),
+        TestConfig::Config(config) => return Ok(config),
+    }
+
+    GLOBAL_CONFIG.get().ok_or(GlobalConfigError::NotInitialized)
+}
+
+/// Replaces the process wide config, whether or not it was initialized
+#[cfg(any(test, feature = "testing"))]
+pub fn override_config(config: Config) {
+    *TEST_CONFIG
+        .write()
+        .unwrap_or_else(|error| error.into_inner()) =
+        TestConfig::Config(Box::leak(Box::new(config)));
+}
+
+/// Clears the process wide config so that [`init`] can be called again
+#[cfg(any(test, feature = "testing"))]
+pub fn reset() {
+    *TEST_CONFIG
+        .write()
+        .unwrap_or_else(|error| error.into_inner()) = TestConfig::Reset;
+}
+
+#[derive(Debug)]
+pub enum GlobalConfigError {
+    NotInitialized,
+    AlreadyInitialized,
+    BuildError(BuildError),
+}
+
+impl fmt::Display for GlobalConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GlobalConfigError::NotInitialized => write!(
+                f,
+                "The global config was accessed before configler::init was called"
+            ),
+            GlobalConfigError::AlreadyInitialized => {
+                write!(f, "The global config has already been initialized")
+            }
+            GlobalConfigError::BuildError(error) => {
+                write!(f, "Failed to build the global config: {}", error)
+            }
+        }
+    }
+}
+
+impl Error for GlobalConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            GlobalConfigError::BuildError(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::sources::YamlConfigSource;
+
+    fn builder_with_user(user: &str) -> ConfigBuilder {
+        let yaml_source = YamlConfigSource::from_str(&format!("database:\n  user: {}\n", user));
+        let mut builder = ConfigBuilder::new();
+        builder.add_custom_source(Box::new(yaml_source.unwrap()));
+        builder
+    }
+
+    // The global config is shared by every test so its lifecycle is checked in one test
+    #[test]
+    fn global_config_lifecycle() {
+        reset();
+        assert!(matches!(config(), Err(GlobalConfigError::NotInitialized)));
+
+        init(&builder_with_user("first")).unwrap();
+        let global_config: &'static Config = config().unwrap();
+        assert_eq!(
+            global_config.get_value("database.user"),
+            Some("first".to_string())
+        );
+        assert!(matches!(
+            init(&builder_with_user("second")),
+            Err(GlobalConfigError::AlreadyInitialized)
+        ));
+
+        override_config(builder_with_user("override").build().unwrap());
+        assert_eq!(
+            config().unwrap().get_value("database.user"),
+            Some("override".to_string())
+        );
+
+        reset();
+        let mut failing_builder = ConfigBuilder::new();
+        failing_builder
+            .add_source(crate::SourceName::YamlFile)
+            .set_config_directory("test_configs/missing");
+        assert!(matches!(
+            init(&failing_builder),
+            Err(GlobalConfigError::BuildError(_))
+        ));
+        assert!(matches!(config(), Err(GlobalConfigError::NotInitialized)));
+    }
+}
@@ -1,29 +1,101 @@
+pub mod bootstrap;
+mod cache;
+#[cfg(feature = "diagnostics")]
+pub mod diagnostics;
+pub mod error;
+pub mod global;
 pub mod sources;
-use std::collections::HashMap;
+use std::{
+    borrow::Cow, cmp::Reverse, collections::HashMap, fmt, io::ErrorKind, str::FromStr, sync::Arc,
+};
 
+use bootstrap::BootstrapSettings;
+use cache::{CachedValue, ValueCache};
 use sources::{
-    config_source::FileError, dot_env::DotEnvironmentConfigSource, ConfigSource,
-    EnvironmentConfigSource, YamlConfigSource,
+    config_source::FileError,
+    dot_env::{DotEnvParseOptions, DotEnvironmentConfigSource},
+    yaml::YamlParseOptions,
+    ConfigSource, DropInDirectory, EnvironmentConfigSource, YamlConfigSource,
 };
 
+pub use error::{BuildError, ConfigError, ConfigValueError};
+pub use global::{config, init};
+
 // sum 2 values and return string
 pub fn sum_as_string(a: usize, b: usize) -> String {
     (a + b).to_string()
 }
 
+/// Resolved configuration. Cloning is cheap as clones share the same sources, and a
+/// `Config` can be shared between threads.
+#[derive(Clone)]
 pub struct Config {
-    sources: Vec<Box<dyn ConfigSource>>,
+    sources: Arc<[Box<dyn ConfigSource>]>,
+    /// Resolved values when caching is enabled, shared by clones
+    cache: Option<Arc<ValueCache>>,
 }
 
 impl Config {
     pub fn get_value(&self, property_name: &str) -> Option<String> {
-        for config_source in self.sources.iter() {
-            let value = config_source.get_value(property_name);
-            if value.is_some() {
-                return value;
+        match &self.cache {
+            None => self.get_value_ref(property_name).map(Cow::into_owned),
+            Some(cache) => {
+                cache
+                    .get_or_resolve(property_name, || self.resolve(property_name))
+                    .value
             }
         }
-        None
+    }
+
+    /// Same as [`Config::get_value`], but borrows the value when the source holds it
+    /// in memory instead of allocating a new `String`. Borrowed lookups always read
+    /// the sources and bypass the value cache.
+    pub fn get_value_ref(&self, property_name: &str) -> Option<Cow<'_, str>> {
+        self.sources
+            .iter()
+            .find_map(|config_source| config_source.get_value_ref(property_name))
+    }
+
+    /// Clears every cached value, e.g. after the process environment has changed
+    pub fn refresh(&self) {
+        if let Some(cache) = &self.cache {
+            cache.clear();
+        }
+    }
+
+    /// Clears the cached values that the named source could change, returning
+    /// whether a source with that name exists
+    pub fn invalidate_source(&self, source_name: &str) -> bool {
+        let source_index = self
+            .sources
+            .iter()
+            .position(|config_source| config_source.get_name() == source_name);
+        if let (Some(cache), Some(source_index)) = (&self.cache, source_index) {
+            cache.invalidate_from(source_index);
+        }
+        source_index.is_some()
+    }
+
+    fn resolve(&self, property_name: &str) -> CachedValue {
+        let resolved = self
+            .sources
+            .iter()
+            .enumerate()
+            .find_map(|(index, config_source)| {
+                config_source
+                    .get_value(property_name)
+                    .map(|value| (index, value))
+            });
+        CachedValue {
+            source_index: resolved.as_ref().map(|(index, _)| *index),
+            value: resolved.map(|(_, value)| value),
+        }
+    }
+
+    /// Returns true when any source defines the property, including sources that
+    /// define it with an empty value
+    pub fn contains_property(&self, property_name: &str) -> bool {
+        self.get_value(property_name).is_some()
     }
 
     pub fn get_value_or_default(&self, property_name: &str, default: String) -> String {
@@ -32,6 +104,73 @@ impl Config {
             None => default,
         }
     }
+
+    /// Returns the value of a property that must be defined
+    pub fn get_required_value(&self, property_name: &str) -> Result<String, ConfigValueError> {
+        self.get_value(property_name)
+            .ok_or_else(|| ConfigValueError::NullError {
+                key: property_name.to_owned(),
+            })
+    }
+
+    /// Parses the value of a property that must be defined
+    pub fn get_parsed_value<T>(&self, property_name: &str) -> Result<T, ConfigValueError>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        let value = self.get_required_value(property_name)?;
+        value
+            .parse()
+            .map_err(|error: T::Err| ConfigValueError::TypeError {
+                key: property_name.to_owned(),
+                expected_type: std::any::type_name::<T>(),
+                message: error.to_string(),
+                value,
+            })
+    }
+}
+
+/// Decides which source wins when several sources define a property
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum PrecedencePolicy {
+    /// Sources with lower ordinals win. The standard ordinals are 100 for the
+    /// environment, 200 for drop-in directories, 265 for YAML and 295 for dotenv files.
+    #[default]
+    LowestOrdinalFirst,
+    /// Sources with higher ordinals win as in smallrye-config. The standard ordinals
+    /// are 300 for the environment, 299 for drop-in directories, 295 for dotenv and
+    /// 265 for YAML files.
+    HighestOrdinalFirst,
+}
+
+impl PrecedencePolicy {
+    /// Ordinal of the environment source unless one is configured
+    pub fn environment_ordinal(self) -> usize {
+        match self {
+            PrecedencePolicy::LowestOrdinalFirst => 100,
+            PrecedencePolicy::HighestOrdinalFirst => 300,
+        }
+    }
+
+    /// Ordinal of YAML files unless one is configured or set with `config_ordinal`
+    pub fn yaml_ordinal(self) -> usize {
+        265
+    }
+
+    /// Ordinal of dotenv files unless one is configured or set with `CONFIG_ORDINAL`
+    pub fn dot_env_ordinal(self) -> usize {
+        295
+    }
+
+    /// Ordinal of drop-in directories unless one is set, so that drop-in files
+    /// override YAML and dotenv files but not the environment
+    pub fn drop_in_ordinal(self) -> usize {
+        match self {
+            PrecedencePolicy::LowestOrdinalFirst => 200,
+            PrecedencePolicy::HighestOrdinalFirst => 299,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -41,37 +180,136 @@ pub enum SourceName {
     YamlFile,
 }
 
+/// A source added to the builder along with any explicit ordinal
+enum SourceEntry {
+    Custom(Box<dyn ConfigSource>, Option<usize>),
+    Lazy(SourceName, Option<usize>),
+    DropIn(DropInDirectory),
+}
+
 pub struct ConfigBuilder {
-    instantiated_sources: Vec<Box<dyn ConfigSource>>,
-    lazy_sources: Vec<SourceName>,
-    config_directory: Option<String>,
+    /// Sources in the order they were added, which breaks ties between sources
+    /// sharing an ordinal
+    sources: Vec<SourceEntry>,
+    bootstrap: BootstrapSettings,
+    bootstrap_file: Option<String>,
+    precedence: PrecedencePolicy,
+    dot_env_options: DotEnvParseOptions,
+    yaml_options: YamlParseOptions,
+    value_cache: bool,
 }
 
 impl ConfigBuilder {
     pub fn new() -> ConfigBuilder {
         ConfigBuilder {
-            instantiated_sources: Vec::new(),
-            lazy_sources: Vec::new(),
-            config_directory: None,
+            sources: Vec::new(),
+            bootstrap: BootstrapSettings::default(),
+            bootstrap_file: None,
+            precedence: PrecedencePolicy::default(),
+            dot_env_options: DotEnvParseOptions::default(),
+            yaml_options: YamlParseOptions::default(),
+            value_cache: false,
         }
     }
 
+    /// Replaces any configured locations with a single config directory
     pub fn set_config_directory(&mut self, config_directory: &str) -> &mut Self {
-        let mut directory = config_directory.to_owned();
-        if !directory.ends_with("/") {
-            directory += "/";
-        }
-        self.config_directory = Some(directory);
+        self.bootstrap.locations = vec![normalize_directory(config_directory)];
+        self
+    }
+
+    /// Appends a directory to the ordered list of config locations. Every location
+    /// containing a file for a lazy source contributes its own source, with locations
+    /// added earlier taking precedence over locations added later.
+    pub fn add_config_location(&mut self, config_location: &str) -> &mut Self {
+        self.bootstrap
+            .locations
+            .push(normalize_directory(config_location));
+        self
+    }
+
+    /// Selects the active profile, e.g. `production`. The `CONFIGLER_PROFILE`
+    /// environment variable takes precedence over the profile set here.
+    pub fn set_profile(&mut self, profile: &str) -> &mut Self {
+        self.bootstrap.profile = Some(profile.to_owned());
+        self
+    }
+
+    /// Replaces every bootstrap setting, including the locations and profile set
+    /// so far. The bootstrap file and environment variables still take precedence.
+    pub fn set_bootstrap_settings(&mut self, settings: BootstrapSettings) -> &mut Self {
+        self.bootstrap = settings;
+        self
+    }
+
+    /// Reads bootstrap settings from a YAML or dotenv file, see [`BootstrapSettings`].
+    /// The `CONFIGLER_BOOTSTRAP_FILE` environment variable takes precedence over the
+    /// file set here.
+    pub fn set_bootstrap_file(&mut self, file_path: &str) -> &mut Self {
+        self.bootstrap_file = Some(file_path.to_owned());
+        self
+    }
+
+    /// Selects whether lower or higher ordinals win. Sources and drop-in directories
+    /// without a configured ordinal get the standard ordinals of the policy when
+    /// building, while custom sources keep the ordinals they report.
+    pub fn set_precedence_policy(&mut self, policy: PrecedencePolicy) -> &mut Self {
+        self.precedence = policy;
+        self
+    }
+
+    /// Options used when parsing lazily loaded dotenv files
+    pub fn set_dot_env_options(&mut self, options: DotEnvParseOptions) -> &mut Self {
+        self.dot_env_options = options;
+        self
+    }
+
+    /// Options used when parsing lazily loaded YAML files. The profile is always
+    /// taken from the bootstrap settings.
+    pub fn set_yaml_options(&mut self, options: YamlParseOptions) -> &mut Self {
+        self.yaml_options = options;
+        self
+    }
+
+    /// Memoizes resolved values, including missing properties, in the built config.
+    /// Use [`Config::refresh`] or [`Config::invalidate_source`] when sources change.
+    pub fn enable_value_cache(&mut self) -> &mut Self {
+        self.value_cache = true;
         self
     }
 
     pub fn add_source(&mut self, name: SourceName) -> &mut Self {
-        self.lazy_sources.push(name);
+        self.sources.push(SourceEntry::Lazy(name, None));
+        self
+    }
+
+    /// Adds a lazily loaded source whose ordinal takes precedence over the bootstrap
+    /// ordinals and any `config_ordinal` in its files. Every file of the dotenv
+    /// cascade shares the ordinal and later files in the cascade take precedence.
+    pub fn add_source_with_ordinal(&mut self, name: SourceName, ordinal: usize) -> &mut Self {
+        self.sources.push(SourceEntry::Lazy(name, Some(ordinal)));
         self
     }
 
     pub fn add_custom_source(&mut self, source: Box<dyn ConfigSource>) -> &mut Self {
-        self.instantiated_sources.push(source);
+        self.sources.push(SourceEntry::Custom(source, None));
+        self
+    }
+
+    /// Adds a source with an ordinal that replaces the one it reports
+    pub fn add_custom_source_with_ordinal(
+        &mut self,
+        source: Box<dyn ConfigSource>,
+        ordinal: usize,
+    ) -> &mut Self {
+        self.sources
+            .push(SourceEntry::Custom(source, Some(ordinal)));
+        self
+    }
+
+    /// Loads every supported file in the directory as its own source
+    pub fn add_drop_in_directory(&mut self, drop_in_directory: DropInDirectory) -> &mut Self {
+        self.sources.push(SourceEntry::DropIn(drop_in_directory));
         self
     }
 
@@ -79,56 +317,247 @@ impl ConfigBuilder {
         self.add_source(SourceName::Environment)
     }
 
-    pub fn build(&self) -> Result<Config, FileError> {
-        let mut final_sources = self.instantiated_sources.clone();
+    /// Builds the config, attempting to load every source. When any source fails
+    /// to load the returned error lists every failure.
+    pub fn build(&self) -> Result<Config, BuildError> {
+        // Lazy sources and drop-in directories depend on the locations and profile
+        let needs_bootstrap = self
+            .sources
+            .iter()
+            .any(|entry| matches!(entry, SourceEntry::Lazy(..) | SourceEntry::DropIn(..)));
+        let settings = if needs_bootstrap {
+            self.bootstrap.resolve(
+                self.bootstrap_file.as_deref(),
+                Box::new(EnvironmentConfigSource::new()),
+            )?
+        } else {
+            self.bootstrap.clone()
+        };
 
-        if !self.lazy_sources.is_empty() {
-            let env_source = EnvironmentConfigSource {};
+        let mut ordered_sources: Vec<(usize, Box<dyn ConfigSource>)> = Vec::new();
+        let mut errors = Vec::new();
+        for entry in self.sources.iter() {
+            match entry {
+                SourceEntry::Custom(source, ordinal) => {
+                    let ordinal = ordinal.unwrap_or_else(|| source.get_ordinal());
+                    ordered_sources.push((ordinal, source.clone()));
+                }
+                SourceEntry::Lazy(name, ordinal) => {
+                    let sources = self.load_lazy_source(name, *ordinal, &settings, &mut errors);
+                    ordered_sources.extend(
+                        sources
+                            .into_iter()
+                            .map(|source| (source.get_ordinal(), source)),
+                    );
+                }
+                SourceEntry::DropIn(drop_in_directory) => {
+                    let (sources, drop_in_errors) = drop_in_directory.load_all(
+                        self.precedence,
+                        &self.yaml_options_for(&settings),
+                        &self.dot_env_options,
+                    );
+                    ordered_sources.extend(
+                        sources
+                            .into_iter()
+                            .map(|source| (source.get_ordinal(), source)),
+                    );
+                    errors.extend(drop_in_errors);
+                }
+            }
+        }
 
-            for source_name in self.lazy_sources.iter() {
-                let config_source: Result<Box<dyn ConfigSource>, FileError> = match source_name {
-                    SourceName::Environment => Ok(Box::new(EnvironmentConfigSource {})),
-                    SourceName::DotEnvironmentFile => {
-                        let file_path = env_source
-                            .get_value("CONFIGLER_DOT_ENVIRONMENT_FILE")
-                            .or(self.config_directory.clone())
-                            .map_or(".env".to_string(), |path| path + ".env");
+        if !errors.is_empty() {
+            return Err(BuildError {
+                errors,
+                ..Default::default()
+            });
+        }
 
-                        match DotEnvironmentConfigSource::from_file(&file_path) {
-                            Ok(source) => Ok(Box::new(source)),
-                            Err(error) => Err(error),
-                        }
-                    }
-                    SourceName::YamlFile => {
-                        let file_path = env_source
-                            .get_value("CONFIGLER_YAML_FILE")
-                            .or(self.config_directory.clone())
-                            .map_or("config.yaml".to_string(), |path| path + "config.yaml");
-
-                        match YamlConfigSource::from_file(&file_path) {
-                            Ok(source) => Ok(Box::new(source)),
-                            //TODO I do wonder if there should be an option to ignore the source if not found
-                            // hmm might have to think about that, Maybe for defaults only?
-                            Err(error) => Err(error),
-                        }
-                    }
-                };
+        // Sort the winning sources first, the sort is stable so sources sharing an
+        // ordinal keep the order they were added in
+        match self.precedence {
+            PrecedencePolicy::LowestOrdinalFirst => {
+                ordered_sources.sort_by_key(|(ordinal, _)| *ordinal)
+            }
+            PrecedencePolicy::HighestOrdinalFirst => {
+                ordered_sources.sort_by_key(|(ordinal, _)| Reverse(*ordinal))
+            }
+        }
+
+        Ok(Config {
+            sources: ordered_sources
+                .into_iter()
+                .map(|(_, source)| source)
+                .collect(),
+            cache: self.value_cache.then(Default::default),
+        })
+    }
+
+    /// The YAML options with the profile selected by the bootstrap settings
+    fn yaml_options_for(&self, settings: &BootstrapSettings) -> YamlParseOptions {
+        YamlParseOptions {
+            profile: settings.profile.clone(),
+            ..self.yaml_options.clone()
+        }
+    }
 
-                if let Ok(source) = config_source {
-                    final_sources.push(source);
-                } else {
-                    return Err(config_source.err().unwrap());
+    /// Loads a lazy source, an explicit `ordinal` overriding the bootstrap ordinals
+    /// and any `config_ordinal` in its files
+    fn load_lazy_source(
+        &self,
+        name: &SourceName,
+        ordinal: Option<usize>,
+        settings: &BootstrapSettings,
+        errors: &mut Vec<FileError>,
+    ) -> Vec<Box<dyn ConfigSource>> {
+        match name {
+            SourceName::Environment => {
+                let mut env_source = EnvironmentConfigSource::new().with_ordinal(
+                    ordinal
+                        .or(settings.environment_ordinal)
+                        .unwrap_or(self.precedence.environment_ordinal()),
+                );
+                if let Some(prefix) = &settings.environment_prefix {
+                    env_source = env_source.with_prefix(prefix);
                 }
+                vec![Box::new(env_source)]
+            }
+            SourceName::DotEnvironmentFile => load_dot_env_cascade(
+                &settings.dot_env.paths(&settings.locations, ".env"),
+                settings,
+                ordinal,
+                settings
+                    .dot_env
+                    .ordinal
+                    .unwrap_or(self.precedence.dot_env_ordinal()),
+                &self.dot_env_options,
+                errors,
+            ),
+            SourceName::YamlFile => {
+                let options = self.yaml_options_for(settings);
+                let yaml_sources = load_from_paths(
+                    &settings.yaml.paths(&settings.locations, "config.yaml"),
+                    !settings.yaml.optional,
+                    errors,
+                    |path| YamlConfigSource::from_file_with_options(path, &options),
+                );
+                yaml_sources
+                    .into_iter()
+                    .map(|source| {
+                        Box::new(match ordinal {
+                            Some(ordinal) => source.with_ordinal(ordinal),
+                            None => source.with_default_ordinal(
+                                settings
+                                    .yaml
+                                    .ordinal
+                                    .unwrap_or(self.precedence.yaml_ordinal()),
+                            ),
+                        }) as Box<dyn ConfigSource>
+                    })
+                    .collect()
             }
         }
+    }
+}
 
-        // Sort sources by ascending ordinal value
-        final_sources.sort_by_key(|s1| s1.as_ref().get_ordinal());
+fn normalize_directory(directory: &str) -> String {
+    let mut directory = directory.to_owned();
+    if !directory.ends_with("/") {
+        directory += "/";
+    }
+    directory
+}
 
-        Ok(Config {
-            sources: final_sources,
-        })
+/// Splits a comma separated list of locations, ignoring blank entries
+fn parse_locations(locations: &str) -> Vec<String> {
+    locations
+        .split(',')
+        .map(|location| location.trim())
+        .filter(|location| !location.is_empty())
+        .map(normalize_directory)
+        .collect()
+}
+
+/// Loads every path that exists. Missing paths are skipped, but an error is recorded
+/// if none of the paths exist for a required file. Every path is attempted and each
+/// failure is added to `errors`.
+fn load_from_paths<T: ConfigSource>(
+    paths: &[String],
+    required: bool,
+    errors: &mut Vec<FileError>,
+    load_file: impl Fn(&str) -> Result<T, FileError>,
+) -> Vec<T> {
+    let mut sources: Vec<T> = Vec::new();
+    let mut first_missing: Option<FileError> = None;
+    let mut found = false;
+
+    for path in paths {
+        match load_file(path) {
+            Ok(source) => {
+                found = true;
+                sources.push(source);
+            }
+            Err(FileError::IoError { path, error }) if error.kind() == ErrorKind::NotFound => {
+                first_missing.get_or_insert(FileError::IoError { path, error });
+            }
+            Err(error) => {
+                found = true;
+                errors.push(error);
+            }
+        }
+    }
+
+    if let Some(error) = first_missing.filter(|_| required && !found) {
+        errors.push(error);
     }
+    sources
+}
+
+/// Loads the dotenv cascade for every base file, e.g. `.env` in each location. The
+/// base file is required unless it is optional, while the overriding `{base}.local`,
+/// `{base}.{profile}` and `{base}.{profile}.local` files are always optional, each
+/// taking precedence over the files before it. An explicit `ordinal` overrides any
+/// `CONFIG_ORDINAL` set in the files, which override the `default_ordinal`.
+fn load_dot_env_cascade(
+    base_paths: &[String],
+    settings: &BootstrapSettings,
+    ordinal: Option<usize>,
+    default_ordinal: usize,
+    options: &DotEnvParseOptions,
+    errors: &mut Vec<FileError>,
+) -> Vec<Box<dyn ConfigSource>> {
+    let mut cascade = vec![
+        (String::new(), !settings.dot_env.optional),
+        (".local".to_string(), false),
+    ];
+    if let Some(profile) = &settings.profile {
+        cascade.push((format!(".{}", profile), false));
+        cascade.push((format!(".{}.local", profile), false));
+    }
+
+    let cascade_ordinal = ordinal.unwrap_or(default_ordinal);
+    let mut cascade_levels = Vec::new();
+    for (suffix, required) in cascade {
+        let paths: Vec<String> = base_paths
+            .iter()
+            .map(|base_path| base_path.to_owned() + &suffix)
+            .collect();
+        let cascade_sources = load_from_paths(&paths, required, errors, |path| {
+            DotEnvironmentConfigSource::from_file_with_options(path, options)
+        });
+        cascade_levels.push(cascade_sources);
+    }
+
+    // The files share an ordinal and sources sharing an ordinal keep their order, so
+    // the cascade is returned with the overriding files first
+    let mut sources: Vec<Box<dyn ConfigSource>> = Vec::new();
+    for source in cascade_levels.into_iter().rev().flatten() {
+        sources.push(Box::new(match ordinal {
+            Some(_) => source.with_ordinal(cascade_ordinal),
+            None => source.with_default_ordinal(cascade_ordinal),
+        }));
+    }
+    sources
 }
 
 impl Default for ConfigBuilder {
@@ -143,19 +572,160 @@ pub trait ConfigPropertyGroup<'a> {
     fn from_config(config: &'a Config) -> Self;
 }
 
-#[derive(Debug, Clone, PartialEq)]
-pub enum ConfigValueError {
-    // TODO these are placeholders, will need to update these once we implement these
-    TypeError,
-    NullError,
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
     use rstest::*;
+    use sources::dot_env::EmptyValuePolicy;
     use std::{env, str::FromStr};
 
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn config_and_sources_are_thread_safe() {
+        assert_send_sync::<Config>();
+        assert_send_sync::<ConfigBuilder>();
+        assert_send_sync::<Box<dyn ConfigSource>>();
+        assert_send_sync::<EnvironmentConfigSource>();
+        assert_send_sync::<DotEnvironmentConfigSource>();
+        assert_send_sync::<YamlConfigSource>();
+    }
+
+    #[test]
+    fn cloned_configs_share_sources() {
+        let config = ConfigBuilder::new()
+            .add_custom_source(Box::new(
+                YamlConfigSource::from_str("database:\n  user: foo\n").unwrap(),
+            ))
+            .build()
+            .unwrap();
+        let cloned_config = config.clone();
+
+        assert!(Arc::ptr_eq(&config.sources, &cloned_config.sources));
+        assert_eq!(
+            cloned_config.get_value("database.user"),
+            Some("foo".to_string())
+        );
+    }
+
+    #[test]
+    fn borrowed_lookups_from_in_memory_sources() {
+        env::set_var("BORROWED_LOOKUP_ENV", "from-env");
+        let config = ConfigBuilder::new()
+            .add_custom_source(Box::new(
+                YamlConfigSource::from_str("borrowed:\n  yaml: from-yaml\n").unwrap(),
+            ))
+            .add_custom_source(Box::new(
+                DotEnvironmentConfigSource::from_str("BORROWED_DOTENV=from-dotenv").unwrap(),
+            ))
+            .add_default_sources()
+            .build()
+            .unwrap();
+
+        let yaml_value = config.get_value_ref("borrowed.yaml");
+        let dot_env_value = config.get_value_ref("borrowed.dotenv");
+        let env_value = config.get_value_ref("borrowed.lookup.env");
+        env::remove_var("BORROWED_LOOKUP_ENV");
+
+        assert!(matches!(yaml_value, Some(Cow::Borrowed("from-yaml"))));
+        assert!(matches!(dot_env_value, Some(Cow::Borrowed("from-dotenv"))));
+        assert!(matches!(env_value, Some(Cow::Owned(value)) if value == "from-env"));
+        assert_eq!(config.get_value_ref("borrowed.missing"), None);
+    }
+
+    #[test]
+    fn cached_values_include_misses_until_refresh() {
+        env::set_var("CACHE_REFRESH_DEFINED", "first");
+        let config = ConfigBuilder::new()
+            .add_default_sources()
+            .enable_value_cache()
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            config.get_value("cache.refresh.defined"),
+            Some("first".to_string())
+        );
+        assert_eq!(config.get_value("cache.refresh.missing"), None);
+
+        env::set_var("CACHE_REFRESH_DEFINED", "second");
+        env::set_var("CACHE_REFRESH_MISSING", "now defined");
+        assert_eq!(
+            config.get_value("cache.refresh.defined"),
+            Some("first".to_string())
+        );
+        assert!(!config.contains_property("cache.refresh.missing"));
+
+        config.refresh();
+        let refreshed = (
+            config.get_value("cache.refresh.defined"),
+            config.get_value("cache.refresh.missing"),
+        );
+        env::remove_var("CACHE_REFRESH_DEFINED");
+        env::remove_var("CACHE_REFRESH_MISSING");
+
+        assert_eq!(
+            refreshed,
+            (Some("second".to_string()), Some("now defined".to_string()))
+        );
+    }
+
+    #[test]
+    fn invalidate_source_keeps_values_from_higher_precedence_sources() {
+        env::set_var("CACHE_SOURCE_SHADOWED", "from-env");
+        let config = ConfigBuilder::new()
+            .add_custom_source(Box::new(
+                YamlConfigSource::from_str("cache:\n  source:\n    yaml: from-yaml\n")
+                    .unwrap()
+                    .with_ordinal(50),
+            ))
+            .add_default_sources()
+            .enable_value_cache()
+            .build()
+            .unwrap();
+        let cache = config.cache.as_ref().unwrap();
+
+        config.get_value("cache.source.yaml");
+        config.get_value("cache.source.shadowed");
+        config.get_value("cache.source.missing");
+        env::remove_var("CACHE_SOURCE_SHADOWED");
+        assert_eq!(cache.len(), 3);
+
+        assert!(config.invalidate_source("EnvironmentConfigSource"));
+        assert_eq!(cache.len(), 1);
+        assert_eq!(
+            config.get_value("cache.source.yaml"),
+            Some("from-yaml".to_string())
+        );
+        assert_eq!(config.get_value("cache.source.shadowed"), None);
+
+        assert!(!config.invalidate_source("UnknownSource"));
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn cache_is_shared_by_clones_and_threads() {
+        let config = ConfigBuilder::new()
+            .add_custom_source(Box::new(
+                DotEnvironmentConfigSource::from_str("SHARED_KEY=value").unwrap(),
+            ))
+            .enable_value_cache()
+            .build()
+            .unwrap();
+
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                let config = config.clone();
+                scope.spawn(move || {
+                    for _ in 0..100 {
+                        assert_eq!(config.get_value("shared.key"), Some("value".to_string()));
+                    }
+                });
+            }
+        });
+        assert_eq!(config.cache.as_ref().unwrap().len(), 1);
+    }
+
     #[test]
     fn it_works() {
         let result = sum_as_string(3, 2);
@@ -224,6 +794,643 @@ mod tests {
         assert_eq!(config.get_value("KEY1"), Some("blah".to_string()));
     }
 
+    #[test]
+    fn earlier_locations_take_precedence() {
+        let config = ConfigBuilder::new()
+            .add_source(SourceName::DotEnvironmentFile)
+            .add_config_location("test_configs/site")
+            .add_config_location("test_configs")
+            .build()
+            .unwrap();
+
+        assert_eq!(config.get_value("KEY1"), Some("site value".to_string()));
+        assert_eq!(config.get_value("SITE_ONLY"), Some("site".to_string()));
+
+        let config = ConfigBuilder::new()
+            .add_source(SourceName::DotEnvironmentFile)
+            .add_config_location("test_configs")
+            .add_config_location("test_configs/site")
+            .build()
+            .unwrap();
+
+        assert_eq!(config.get_value("KEY1"), Some("blah".to_string()));
+        assert_eq!(config.get_value("SITE_ONLY"), Some("site".to_string()));
+    }
+
+    #[test]
+    fn locations_missing_a_file_are_skipped() {
+        let build_result = ConfigBuilder::new()
+            .add_source(SourceName::YamlFile)
+            .add_config_location("test_configs/does-not-exist")
+            .add_config_location("test_configs")
+            .build();
+        assert!(build_result.is_ok());
+
+        let config = build_result.unwrap();
+        assert_eq!(config.get_value("database.user"), Some("foo".to_string()));
+    }
+
+    #[test]
+    fn errors_when_no_location_has_the_file() {
+        let build_result = ConfigBuilder::new()
+            .add_source(SourceName::YamlFile)
+            .add_config_location("test_configs/does-not-exist")
+            .add_config_location("test_configs/also-missing")
+            .build();
+
+        assert!(matches!(
+            build_result.err().unwrap().errors(),
+            [FileError::IoError { .. }]
+        ));
+    }
+
+    #[test]
+    fn bootstrap_file_names_yaml_file_used_as_is() {
+        let config = ConfigBuilder::new()
+            .add_source(SourceName::YamlFile)
+            .set_bootstrap_file("test_configs/bootstrap/configler.yaml")
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            config.get_value("database.user"),
+            Some("site-user".to_string())
+        );
+    }
+
+    #[test]
+    fn optional_sources_may_be_missing() {
+        let mut settings = BootstrapSettings::default();
+        settings.yaml.optional = true;
+        settings.dot_env.optional = true;
+        let build_result = ConfigBuilder::new()
+            .add_source(SourceName::YamlFile)
+            .add_source(SourceName::DotEnvironmentFile)
+            .set_bootstrap_settings(settings)
+            .set_config_directory("test_configs/does-not-exist")
+            .build();
+
+        assert!(build_result.is_ok());
+    }
+
+    #[test]
+    fn dot_env_cascade_follows_the_named_file() {
+        let mut settings = BootstrapSettings {
+            profile: Some("production".to_string()),
+            ..Default::default()
+        };
+        settings.dot_env.file = Some("test_configs/cascade/.env".to_string());
+        let config = ConfigBuilder::new()
+            .add_source(SourceName::DotEnvironmentFile)
+            .set_bootstrap_settings(settings)
+            .set_config_directory("test_configs/does-not-exist")
+            .build()
+            .unwrap();
+
+        assert_eq!(config.get_value("CASCADE_A"), Some("env".to_string()));
+        assert_eq!(config.get_value("CASCADE_B"), Some("local".to_string()));
+        assert_eq!(
+            config.get_value("CASCADE_D"),
+            Some("production-local".to_string())
+        );
+    }
+
+    #[test]
+    fn bootstrap_ordinals_and_environment_prefix() {
+        env::set_var("BOOTSTRAP_ORDINAL_TEST_DATABASE_USER", "env-user");
+        let mut settings = BootstrapSettings {
+            locations: vec!["test_configs/site".to_string()],
+            environment_prefix: Some("BOOTSTRAP_ORDINAL_TEST_".to_string()),
+            ..Default::default()
+        };
+        let build = |settings: &BootstrapSettings| {
+            ConfigBuilder::new()
+                .add_source(SourceName::Environment)
+                .add_source(SourceName::YamlFile)
+                .set_bootstrap_settings(settings.clone())
+                .build()
+                .unwrap()
+        };
+
+        assert_eq!(
+            build(&settings).get_value("database.user"),
+            Some("env-user".to_string())
+        );
+        settings.yaml.ordinal = Some(50);
+        assert_eq!(
+            build(&settings).get_value("database.user"),
+            Some("site-user".to_string())
+        );
+        env::remove_var("BOOTSTRAP_ORDINAL_TEST_DATABASE_USER");
+    }
+
+    #[test]
+    fn explicit_ordinals_override_file_and_default_ordinals() {
+        let custom_source = || {
+            Box::new(DotEnvironmentConfigSource::from_str("ORDINAL_OWNER=custom").unwrap())
+                as Box<dyn ConfigSource>
+        };
+        let config = ConfigBuilder::new()
+            .add_custom_source_with_ordinal(custom_source(), 60)
+            .add_source(SourceName::YamlFile)
+            .set_config_directory("test_configs/ordinal")
+            .build()
+            .unwrap();
+        assert_eq!(config.get_value("ordinal.owner"), Some("yaml".to_string()));
+
+        let config = ConfigBuilder::new()
+            .add_custom_source_with_ordinal(custom_source(), 60)
+            .add_source_with_ordinal(SourceName::YamlFile, 70)
+            .set_config_directory("test_configs/ordinal")
+            .build()
+            .unwrap();
+        assert_eq!(
+            config.get_value("ordinal.owner"),
+            Some("custom".to_string())
+        );
+    }
+
+    #[test]
+    fn sources_sharing_an_ordinal_keep_insertion_order() {
+        let source = |value: &str| {
+            Box::new(DotEnvironmentConfigSource::from_str(&format!("TIE_OWNER={}", value)).unwrap())
+                as Box<dyn ConfigSource>
+        };
+        let config = ConfigBuilder::new()
+            .add_source_with_ordinal(SourceName::YamlFile, 100)
+            .add_custom_source_with_ordinal(source("first"), 100)
+            .add_custom_source_with_ordinal(source("second"), 100)
+            .set_config_directory("test_configs/ordinal")
+            .build()
+            .unwrap();
+
+        let source_names: Vec<&str> = config
+            .sources
+            .iter()
+            .map(|source| source.get_name())
+            .collect();
+        assert_eq!(
+            source_names,
+            vec![
+                "YamlConfigSource[test_configs/ordinal/config.yaml]",
+                "DotEnvironmentConfigSource",
+                "DotEnvironmentConfigSource",
+            ]
+        );
+        assert_eq!(config.get_value("TIE_OWNER"), Some("first".to_string()));
+    }
+
+    #[test]
+    fn build_reports_every_failing_source() {
+        let build_result = ConfigBuilder::new()
+            .add_source(SourceName::DotEnvironmentFile)
+            .add_source(SourceName::YamlFile)
+            .set_config_directory("test_configs/errors")
+            .add_drop_in_directory(DropInDirectory::new("test_configs/missing.d"))
+            .build();
+
+        let build_error = build_result.err().unwrap();
+        let failed_paths: Vec<&str> = build_error.errors().iter().map(FileError::path).collect();
+        assert_eq!(
+            failed_paths,
+            vec![
+                "test_configs/errors/.env",
+                "test_configs/errors/config.yaml",
+                "test_configs/missing.d"
+            ]
+        );
+        assert!(matches!(
+            build_error.errors(),
+            [
+                FileError::DotEnvLineParseErrors { .. },
+                FileError::IoError { .. },
+                FileError::IoError { .. }
+            ]
+        ));
+
+        let message = build_error.to_string();
+        assert!(
+            message.starts_with("Failed to build the config, found 3 error(s):\n- Failed to parse")
+        );
+        assert!(message.contains("\n  Line 2, column 1: Record has invalid '=' operand\n"));
+        assert!(message.contains("\n- test_configs/errors/config.yaml: "));
+    }
+
+    #[test]
+    fn set_config_directory_replaces_locations() {
+        let config = ConfigBuilder::new()
+            .add_source(SourceName::DotEnvironmentFile)
+            .add_config_location("test_configs/site")
+            .set_config_directory("test_configs")
+            .build()
+            .unwrap();
+
+        assert_eq!(config.get_value("KEY1"), Some("blah".to_string()));
+        assert_eq!(config.get_value("SITE_ONLY"), None);
+    }
+
+    #[test]
+    fn drop_in_files_follow_the_profile_and_dot_env_options() {
+        let config = ConfigBuilder::new()
+            .set_profile("prod")
+            .set_dot_env_options(DotEnvParseOptions {
+                empty_values: EmptyValuePolicy::Unset,
+                ..Default::default()
+            })
+            .add_drop_in_directory(DropInDirectory::new("test_configs/profiles.d").exclude("30-*"))
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            config.get_value("database.host"),
+            Some("db.prod.internal".to_string())
+        );
+        assert_eq!(config.get_value("database.name"), None);
+    }
+
+    #[test]
+    fn drop_in_files_follow_strict_yaml_mode() {
+        let build_result = ConfigBuilder::new()
+            .set_yaml_options(YamlParseOptions {
+                strict: true,
+                ..Default::default()
+            })
+            .add_drop_in_directory(
+                DropInDirectory::new("test_configs/profiles.d").include("*.yaml"),
+            )
+            .build();
+
+        let build_error = build_result.err().unwrap();
+        let failed_paths: Vec<&str> = build_error.errors().iter().map(FileError::path).collect();
+        assert_eq!(failed_paths, vec!["test_configs/profiles.d/30-strict.yaml"]);
+    }
+
+    #[rstest]
+    #[case(PrecedencePolicy::LowestOrdinalFirst)]
+    #[case(PrecedencePolicy::HighestOrdinalFirst)]
+    fn drop_in_files_override_config_files(#[case] policy: PrecedencePolicy) {
+        let config = ConfigBuilder::new()
+            .set_precedence_policy(policy)
+            .add_source(SourceName::YamlFile)
+            .set_config_directory("test_configs")
+            .add_drop_in_directory(DropInDirectory::new("test_configs/config.d"))
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            config.get_value("database.user"),
+            Some("local-user".to_string())
+        );
+        assert_eq!(
+            config.get_value("database.host"),
+            Some("db.internal".to_string())
+        );
+        assert_eq!(
+            config.get_value("database.password"),
+            Some("base-password".to_string())
+        );
+    }
+
+    #[rstest]
+    #[case(None, "env", "local", "local", "local")]
+    #[case(Some("staging"), "env", "local", "local", "local")]
+    #[case(Some("production"), "env", "local", "production", "production-local")]
+    fn dot_env_cascade_precedence(
+        #[case] profile: Option<&str>,
+        #[case] a: &str,
+        #[case] b: &str,
+        #[case] c: &str,
+        #[case] d: &str,
+    ) {
+        let mut builder = ConfigBuilder::new();
+        builder
+            .add_source(SourceName::DotEnvironmentFile)
+            .set_config_directory("test_configs/cascade");
+        if let Some(profile) = profile {
+            builder.set_profile(profile);
+        }
+        let config = builder.build().unwrap();
+
+        assert_eq!(config.get_value("cascade.a"), Some(a.to_string()));
+        assert_eq!(config.get_value("cascade.b"), Some(b.to_string()));
+        assert_eq!(config.get_value("cascade.c"), Some(c.to_string()));
+        assert_eq!(config.get_value("cascade.d"), Some(d.to_string()));
+    }
+
+    #[rstest]
+    #[case(PrecedencePolicy::LowestOrdinalFirst, "yaml")]
+    #[case(PrecedencePolicy::HighestOrdinalFirst, "dotenv")]
+    fn precedence_policy_decides_the_winning_source(
+        #[case] policy: PrecedencePolicy,
+        #[case] file_owner: &str,
+    ) {
+        env::set_var("PRECEDENCE_POLICY_TEST_PRECEDENCE_SHARED", "environment");
+        let config = ConfigBuilder::new()
+            .set_precedence_policy(policy)
+            .add_source(SourceName::Environment)
+            .add_source(SourceName::YamlFile)
+            .add_source(SourceName::DotEnvironmentFile)
+            .set_bootstrap_settings(BootstrapSettings {
+                locations: vec!["test_configs/precedence".to_string()],
+                environment_prefix: Some("PRECEDENCE_POLICY_TEST_".to_string()),
+                ..Default::default()
+            })
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            config.get_value("precedence.owner"),
+            Some(file_owner.to_string())
+        );
+        assert_eq!(
+            config.get_value("precedence.shared"),
+            Some("environment".to_string())
+        );
+    }
+
+    #[test]
+    fn precedence_policy_resolves_ordinals_regardless_of_call_order() {
+        let mut builder = ConfigBuilder::new();
+        builder
+            .set_precedence_policy(PrecedencePolicy::HighestOrdinalFirst)
+            .add_source(SourceName::Environment)
+            .add_source(SourceName::YamlFile)
+            .add_drop_in_directory(DropInDirectory::new("test_configs/config.d"))
+            .set_bootstrap_settings(BootstrapSettings::default())
+            .set_config_directory("test_configs");
+        let ordinals = |builder: &ConfigBuilder| -> Vec<usize> {
+            let config = builder.build().unwrap();
+            config
+                .sources
+                .iter()
+                .map(|source| source.get_ordinal())
+                .collect()
+        };
+
+        assert_eq!(ordinals(&builder), vec![300, 299, 299, 299, 265]);
+        builder.set_precedence_policy(PrecedencePolicy::LowestOrdinalFirst);
+        assert_eq!(ordinals(&builder), vec![100, 200, 200, 200, 265]);
+    }
+
+    #[rstest]
+    #[case(PrecedencePolicy::LowestOrdinalFirst)]
+    #[case(PrecedencePolicy::HighestOrdinalFirst)]
+    fn later_files_win_under_both_policies(#[case] policy: PrecedencePolicy) {
+        let config = ConfigBuilder::new()
+            .set_precedence_policy(policy)
+            .add_source(SourceName::DotEnvironmentFile)
+            .set_config_directory("test_configs/cascade")
+            .set_profile("production")
+            .add_drop_in_directory(DropInDirectory::new("test_configs/config.d"))
+            .build()
+            .unwrap();
+
+        assert_eq!(config.get_value("cascade.b"), Some("local".to_string()));
+        assert_eq!(
+            config.get_value("cascade.d"),
+            Some("production-local".to_string())
+        );
+        assert_eq!(
+            config.get_value("database.host"),
+            Some("db.internal".to_string())
+        );
+        assert_eq!(
+            config.get_value("database.password"),
+            Some("base-password".to_string())
+        );
+    }
+
+    #[rstest]
+    #[case(PrecedencePolicy::LowestOrdinalFirst, None)]
+    #[case(PrecedencePolicy::HighestOrdinalFirst, None)]
+    #[case(PrecedencePolicy::LowestOrdinalFirst, Some(0))]
+    #[case(PrecedencePolicy::HighestOrdinalFirst, Some(0))]
+    fn drop_in_directories_rank_as_one_source_whatever_their_file_count(
+        #[case] policy: PrecedencePolicy,
+        #[case] ordinal: Option<usize>,
+    ) {
+        // More files than the gap between the drop-in and dotenv ordinals
+        let mut drop_in_directory = DropInDirectory::new("test_configs/many.d");
+        if let Some(ordinal) = ordinal {
+            drop_in_directory = drop_in_directory.ordinal(ordinal);
+        }
+        let config = ConfigBuilder::new()
+            .set_precedence_policy(policy)
+            .add_source(SourceName::DotEnvironmentFile)
+            .set_config_directory("test_configs/cascade")
+            .add_drop_in_directory(drop_in_directory)
+            .build()
+            .unwrap();
+
+        let expected_a = match (policy, ordinal) {
+            // Ordinal 0 ranks the directory last when the highest ordinal wins
+            (PrecedencePolicy::HighestOrdinalFirst, Some(0)) => "env",
+            _ => "drop-in-10",
+        };
+        assert_eq!(config.get_value("cascade.a"), Some(expected_a.to_string()));
+        assert_eq!(
+            config.get_value("drop-in.winner"),
+            Some("drop-in-60".to_string())
+        );
+    }
+
+    #[test]
+    fn highest_ordinal_first_sorts_custom_sources_descending() {
+        let source = |value: &str, ordinal: usize| {
+            Box::new(
+                DotEnvironmentConfigSource::from_str(&format!("RANK_OWNER={}", value))
+                    .unwrap()
+                    .with_ordinal(ordinal),
+            ) as Box<dyn ConfigSource>
+        };
+        let config = ConfigBuilder::new()
+            .set_precedence_policy(PrecedencePolicy::HighestOrdinalFirst)
+            .add_custom_source(source("low", 100))
+            .add_custom_source(source("high", 400))
+            .add_custom_source_with_ordinal(source("tied", 100), 400)
+            .build()
+            .unwrap();
+
+        assert_eq!(config.get_value("RANK_OWNER"), Some("high".to_string()));
+        let owners: Vec<String> = config
+            .sources
+            .iter()
+            .filter_map(|source| source.get_value("RANK_OWNER"))
+            .collect();
+        assert_eq!(owners, vec!["high", "tied", "low"]);
+    }
+
+    #[rstest]
+    #[case(0)]
+    #[case(1)]
+    #[case(295)]
+    fn explicit_ordinals_keep_the_dot_env_cascade_order(#[case] ordinal: usize) {
+        let config = ConfigBuilder::new()
+            .add_source_with_ordinal(SourceName::DotEnvironmentFile, ordinal)
+            .set_config_directory("test_configs/cascade")
+            .set_profile("production")
+            .build()
+            .unwrap();
+
+        assert!(config
+            .sources
+            .iter()
+            .all(|source| source.get_ordinal() == ordinal));
+        assert_eq!(config.get_value("cascade.a"), Some("env".to_string()));
+        assert_eq!(config.get_value("cascade.b"), Some("local".to_string()));
+        assert_eq!(
+            config.get_value("cascade.c"),
+            Some("production".to_string())
+        );
+        assert_eq!(
+            config.get_value("cascade.d"),
+            Some("production-local".to_string())
+        );
+    }
+
+    #[test]
+    fn dot_env_cascade_is_optional() {
+        let build_result = ConfigBuilder::new()
+            .add_source(SourceName::DotEnvironmentFile)
+            .set_config_directory("test_configs")
+            .set_profile("production")
+            .build();
+        assert!(build_result.is_ok());
+
+        let config = build_result.unwrap();
+        assert_eq!(config.get_value("KEY1"), Some("blah".to_string()));
+    }
+
+    #[test]
+    fn yaml_options_apply_to_lazy_sources() {
+        let build_result = ConfigBuilder::new()
+            .add_source(SourceName::YamlFile)
+            .set_config_directory("test_configs/strict")
+            .set_yaml_options(YamlParseOptions {
+                strict: true,
+                ..Default::default()
+            })
+            .build();
+
+        assert!(matches!(
+            build_result.err().unwrap().errors(),
+            [FileError::YamlParseError { .. }]
+        ));
+    }
+
+    #[test]
+    fn dot_env_options_apply_to_lazy_sources() {
+        env::set_var("BUILDER_INTERPOLATION_USER", "env-user");
+
+        let build_result = ConfigBuilder::new()
+            .add_source(SourceName::DotEnvironmentFile)
+            .set_config_directory("test_configs/interpolation")
+            .set_dot_env_options(DotEnvParseOptions {
+                interpolate_environment: true,
+                ..Default::default()
+            })
+            .build();
+
+        env::remove_var("BUILDER_INTERPOLATION_USER");
+
+        let config = build_result.unwrap();
+        assert_eq!(
+            config.get_value("database.url"),
+            Some("postgres://env-user@db.internal/app".to_string())
+        );
+    }
+
+    #[test]
+    fn present_but_empty_differs_from_absent() {
+        let dot_env_str = "
+        BLANKED=
+        ";
+        let dot_env_source = DotEnvironmentConfigSource::from_str_with_options(
+            dot_env_str,
+            &DotEnvParseOptions {
+                empty_values: EmptyValuePolicy::EmptyString,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let config = ConfigBuilder::new()
+            .add_custom_source(Box::new(dot_env_source))
+            .build()
+            .unwrap();
+
+        assert_eq!(config.get_value("blanked"), Some(String::new()));
+        assert!(config.contains_property("blanked"));
+        assert_eq!(
+            config.get_value_or_default("blanked", "default".to_string()),
+            ""
+        );
+
+        assert_eq!(config.get_value("absent"), None);
+        assert!(!config.contains_property("absent"));
+        assert_eq!(
+            config.get_value_or_default("absent", "default".to_string()),
+            "default"
+        );
+    }
+
+    #[test]
+    fn yaml_scalars_do_not_fall_through() {
+        let yaml_source = YamlConfigSource::from_str(
+            "
+        feature:
+            enabled: false
+            retries: 3
+            owner: ~
+        ",
+        )
+        .unwrap();
+        let dot_env_source = DotEnvironmentConfigSource::from_str(
+            "
+        FEATURE_ENABLED=true
+        FEATURE_RETRIES=10
+        FEATURE_OWNER=someone
+        ",
+        )
+        .unwrap();
+
+        let config = ConfigBuilder::new()
+            .add_custom_source(Box::new(dot_env_source))
+            .add_custom_source(Box::new(yaml_source))
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            config.get_value("feature.enabled"),
+            Some("false".to_string())
+        );
+        assert_eq!(config.get_value("feature.retries"), Some("3".to_string()));
+        assert_eq!(config.get_value("feature.owner"), Some(String::new()));
+    }
+
+    #[test]
+    fn profile_selects_yaml_documents() {
+        let config = ConfigBuilder::new()
+            .add_source(SourceName::YamlFile)
+            .set_config_directory("test_configs/profiles")
+            .set_profile("prod")
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            config.get_value("database.host"),
+            Some("db.prod.internal".to_string())
+        );
+        assert_eq!(config.get_value("database.user"), Some("app".to_string()));
+    }
+
+    #[rstest]
+    #[case("/etc/app,./config", vec!["/etc/app/", "./config/"])]
+    #[case(" /etc/app/ , , ./config ", vec!["/etc/app/", "./config/"])]
+    #[case("", vec![])]
+    fn parse_location_list(#[case] locations: String, #[case] expected: Vec<&str>) {
+        assert_eq!(parse_locations(&locations), expected);
+    }
+
     #[test]
     fn overrides_respect_ordinal_values() {
         env::set_var("KEY1", "Overrided Value");
@@ -1,13 +1,27 @@
 use core::fmt;
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    error::Error,
+    sync::{Arc, OnceLock, PoisonError, RwLock},
+};
 
 use dyn_clone::DynClone;
 
 use super::dot_env::DotEnvLineParseErrors;
 use super::yaml::YamlParseError;
 
-pub trait ConfigSource: DynClone {
+/// A source of configuration values. Sources must be thread safe so that a
+/// [`Config`](crate::Config) can be shared between threads.
+pub trait ConfigSource: DynClone + Send + Sync {
     fn get_ordinal(&self) -> usize;
     fn get_value(&self, property_name: &str) -> Option<String>;
+
+    /// Looks up a property without copying values the source holds in memory.
+    /// Sources that only implement `get_value` return owned values.
+    fn get_value_ref(&self, property_name: &str) -> Option<Cow<'_, str>> {
+        self.get_value(property_name).map(Cow::Owned)
+    }
     fn get_name(&self) -> &str;
 
     fn from_file(file_path: &str) -> Result<Self, FileError>
@@ -17,25 +31,99 @@ pub trait ConfigSource: DynClone {
 
 dyn_clone::clone_trait_object!(ConfigSource);
 
+/// Property a file may define to set the ordinal of its source, as in smallrye-config.
+/// Ordinals set explicitly on the source or builder take precedence over it.
+pub const CONFIG_ORDINAL_PROPERTY: &str = "config_ordinal";
+
 pub fn convert_property_to_environment_name(property_name: &str) -> String {
-    // TODO add more conversion rules
+    // Every character that is not alphanumeric is replaced with an underscore
     // https://smallrye.io/smallrye-config/Main/config/environment-variables/
-    str::replace(&property_name.to_uppercase(), ".", "_")
+    property_name
+        .chars()
+        .map(|char| {
+            if char.is_ascii_alphanumeric() {
+                char.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
 }
 
+/// Upper bound on cached environment names, guarding against unbounded growth when
+/// property names are generated dynamically
+const ENVIRONMENT_NAME_CACHE_LIMIT: usize = 4096;
+
+static ENVIRONMENT_NAME_CACHE: OnceLock<RwLock<HashMap<Box<str>, Arc<str>>>> = OnceLock::new();
+
+/// Same as [`convert_property_to_environment_name`], but remembers each conversion so
+/// that repeated lookups of a property do not allocate
+pub fn cached_environment_name(property_name: &str) -> Arc<str> {
+    let cache = ENVIRONMENT_NAME_CACHE.get_or_init(Default::default);
+    if let Some(env_name) = cache
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(property_name)
+    {
+        return Arc::clone(env_name);
+    }
+
+    let env_name: Arc<str> = convert_property_to_environment_name(property_name).into();
+    let mut cache = cache.write().unwrap_or_else(PoisonError::into_inner);
+    if cache.len() < ENVIRONMENT_NAME_CACHE_LIMIT {
+        cache.insert(property_name.into(), Arc::clone(&env_name));
+    }
+    env_name
+}
+
+/// A source file that could not be read or parsed, along with its path
 #[derive(Debug)]
 pub enum FileError {
-    DotEnvLineParseErrors(DotEnvLineParseErrors),
-    YamlParseError(YamlParseError),
-    IoError(std::io::Error),
+    DotEnvLineParseErrors {
+        path: String,
+        errors: DotEnvLineParseErrors,
+    },
+    YamlParseError {
+        path: String,
+        error: YamlParseError,
+    },
+    IoError {
+        path: String,
+        error: std::io::Error,
+    },
+}
+
+impl FileError {
+    /// Path of the file or directory that caused the error
+    pub fn path(&self) -> &str {
+        match self {
+            FileError::DotEnvLineParseErrors { path, .. }
+            | FileError::YamlParseError { path, .. }
+            | FileError::IoError { path, .. } => path,
+        }
+    }
 }
 
 impl fmt::Display for FileError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            FileError::IoError(error) => write!(f, "{}", error),
-            FileError::DotEnvLineParseErrors(error) => write!(f, "{}", error),
-            FileError::YamlParseError(error) => write!(f, "{}", error),
+            FileError::IoError { path, error } => write!(f, "{}: {}", path, error),
+            FileError::DotEnvLineParseErrors { path, errors } => {
+                write!(f, "Failed to parse '{}': {}", path, errors)
+            }
+            FileError::YamlParseError { path, error } => {
+                write!(f, "Failed to parse '{}': {}", path, error)
+            }
+        }
+    }
+}
+
+impl Error for FileError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            FileError::IoError { error, .. } => Some(error),
+            FileError::DotEnvLineParseErrors { errors, .. } => Some(errors),
+            FileError::YamlParseError { error, .. } => Some(error),
         }
     }
 }
@@ -50,16 +138,26 @@ mod tests {
     #[case("TEST.ONE", "TEST_ONE")]
     #[case("test.ONE", "TEST_ONE")]
     #[case("foo", "FOO")]
-    // TODO adding more conversion rules
-    // #[case("foo.\"bar\".baz", "FOO__BAR__BAZ")]
-    // #[case("foo.bar-baz", "FOO_BAR_BAZ")]
-    // #[case("foo.bar[0]", "FOO_BAR_0_")]
-    // #[case("foo.bar[0].baz", "FOO_BAR_0__BAZ")]
+    #[case("foo.\"bar\".baz", "FOO__BAR__BAZ")]
+    #[case("foo.bar-baz", "FOO_BAR_BAZ")]
+    #[case("foo.bar[0]", "FOO_BAR_0_")]
+    #[case("foo.bar[0].baz", "FOO_BAR_0__BAZ")]
     fn convert_property_to_environment_name_rules(
         #[case] property_name: String,
         #[case] expected_env_name: String,
     ) {
         let env_name = convert_property_to_environment_name(&property_name);
         assert_eq!(expected_env_name, env_name);
+        assert_eq!(
+            expected_env_name,
+            cached_environment_name(&property_name).as_ref()
+        );
+    }
+
+    #[test]
+    fn cached_environment_names_are_reused() {
+        let first = cached_environment_name("cache.reuse-test");
+        let second = cached_environment_name("cache.reuse-test");
+        assert!(Arc::ptr_eq(&first, &second));
     }
 }
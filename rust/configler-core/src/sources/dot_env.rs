@@ -1,42 +1,256 @@
 use super::{
-    config_source::{convert_property_to_environment_name, FileError},
+    config_source::{
+        cached_environment_name, convert_property_to_environment_name, FileError,
+        CONFIG_ORDINAL_PROPERTY,
+    },
     ConfigSource,
 };
+#[cfg(feature = "diagnostics")]
+use crate::diagnostics::Diagnostic;
 use core::fmt;
-use regex::Regex;
-use std::{collections::HashMap, fs, str::FromStr};
+use std::{borrow::Cow, collections::HashMap, env, error::Error, fs, ops::Range, str::FromStr};
 
 // https://www.dotenv.org/docs/security/env
 #[derive(Clone, Debug)]
 pub struct DotEnvironmentConfigSource {
     values: HashMap<String, String>,
+    warnings: Vec<DotEnvWarning>,
+    ordinal: usize,
+    /// Whether the ordinal came from the `CONFIG_ORDINAL` key
+    has_config_ordinal: bool,
+    name: String,
+}
+
+/// Options controlling how dotenv content is parsed
+#[derive(Debug, Clone, Default)]
+pub struct DotEnvParseOptions {
+    /// Resolve `${VAR}` references that are not defined earlier in the file
+    /// against the process environment
+    pub interpolate_environment: bool,
+    pub empty_values: EmptyValuePolicy,
+    pub duplicate_keys: DuplicateKeyPolicy,
+}
+
+/// How an explicitly empty assignment such as `KEY=` or `KEY=""` is handled
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum EmptyValuePolicy {
+    /// Reject the line with a parse error
+    #[default]
+    Error,
+    /// Keep the key with an empty string value
+    EmptyString,
+    /// Ignore the assignment as if the key was never written
+    Unset,
+}
+
+/// How a key assigned more than once in the same file is handled
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum DuplicateKeyPolicy {
+    /// Reject the repeated assignment with a parse error
+    Error,
+    /// Keep the first assignment and ignore the rest
+    FirstWins,
+    /// Keep the last assignment and record a [`DotEnvWarning`]
+    #[default]
+    LastWinsWithWarning,
+}
+
+/// Non fatal problems found while parsing a dotenv file
+#[derive(Debug, Clone, PartialEq)]
+pub enum DotEnvWarning {
+    DuplicateKey {
+        key: String,
+        line: usize,
+        previous_line: usize,
+    },
+}
+
+impl fmt::Display for DotEnvWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DotEnvWarning::DuplicateKey {
+                key,
+                line,
+                previous_line,
+            } => write!(
+                f,
+                "Line {}: {} overrides the value assigned on line {}",
+                line, key, previous_line
+            ),
+        }
+    }
+}
+
+impl DotEnvironmentConfigSource {
+    /// Overrides the default ordinal of this source, including one set with
+    /// `CONFIG_ORDINAL`
+    pub fn with_ordinal(mut self, ordinal: usize) -> Self {
+        self.ordinal = ordinal;
+        self
+    }
+
+    /// Sets the ordinal unless the content sets its own with `CONFIG_ORDINAL`
+    pub(crate) fn with_default_ordinal(self, ordinal: usize) -> Self {
+        if self.has_config_ordinal {
+            self
+        } else {
+            self.with_ordinal(ordinal)
+        }
+    }
+
+    /// Warnings collected while parsing the source
+    pub fn warnings(&self) -> &[DotEnvWarning] {
+        &self.warnings
+    }
+
+    pub fn from_file_with_options(
+        file_path: &str,
+        options: &DotEnvParseOptions,
+    ) -> Result<Self, FileError> {
+        match fs::read_to_string(file_path) {
+            Err(error) => Err(FileError::IoError {
+                path: file_path.to_owned(),
+                error,
+            }),
+            Ok(file_content) => {
+                match DotEnvironmentConfigSource::from_str_with_options(&file_content, options) {
+                    Err(errors) => Err(FileError::DotEnvLineParseErrors {
+                        path: file_path.to_owned(),
+                        errors,
+                    }),
+                    Ok(config_source) => Ok(DotEnvironmentConfigSource {
+                        name: format!("{}[{}]", config_source.name, file_path),
+                        ..config_source
+                    }),
+                }
+            }
+        }
+    }
+
+    pub fn from_str_with_options(
+        dot_env_str: &str,
+        options: &DotEnvParseOptions,
+    ) -> Result<Self, DotEnvLineParseErrors> {
+        let mut parse_errors = DotEnvLineParseErrors {
+            line_errors: Vec::new(),
+        };
+        let mut warnings = Vec::new();
+        let mut key_value_map: HashMap<String, String> = HashMap::new();
+        let mut key_lines: HashMap<String, usize> = HashMap::new();
+        let config_ordinal_key = cached_environment_name(CONFIG_ORDINAL_PROPERTY);
+        let mut config_ordinal = None;
+        for result_record in parse_records(dot_env_str) {
+            let mut record = match result_record {
+                Err(line_error) => {
+                    parse_errors.line_errors.push(line_error);
+                    continue;
+                }
+                Ok(record) => record,
+            };
+            // Keys are stored by environment name, the form property names are looked
+            // up by, so `MY-KEY` is found as `my-key`
+            record.key = convert_property_to_environment_name(&record.key);
+
+            // Single quoted and backtick values are always taken literally
+            let value = match record.quote {
+                None => interpolate(&record.value, false, &key_value_map, options),
+                Some('"') => interpolate(&record.escaped_value, true, &key_value_map, options),
+                Some(_) => record.value,
+            };
+            // Values that interpolate to nothing, e.g. `${UNSET}`, count as empty
+            if value.is_empty() {
+                match options.empty_values {
+                    EmptyValuePolicy::Error => {
+                        parse_errors.line_errors.push((
+                            record.line,
+                            record.value_column,
+                            LineParseError::ValueIsEmpty,
+                        ));
+                        continue;
+                    }
+                    EmptyValuePolicy::Unset => continue,
+                    EmptyValuePolicy::EmptyString => {}
+                }
+            }
+
+            if let Some(&previous_line) = key_lines.get(&record.key) {
+                match options.duplicate_keys {
+                    DuplicateKeyPolicy::Error => {
+                        parse_errors.line_errors.push((
+                            record.line,
+                            record.column,
+                            LineParseError::DuplicateKey,
+                        ));
+                        continue;
+                    }
+                    DuplicateKeyPolicy::FirstWins => continue,
+                    DuplicateKeyPolicy::LastWinsWithWarning => {
+                        warnings.push(DotEnvWarning::DuplicateKey {
+                            key: record.key.clone(),
+                            line: record.line,
+                            previous_line,
+                        })
+                    }
+                }
+            }
+
+            if record.key == *config_ordinal_key {
+                match value.trim().parse::<usize>() {
+                    Ok(ordinal) => config_ordinal = Some(ordinal),
+                    Err(_) => {
+                        parse_errors.line_errors.push((
+                            record.line,
+                            record.value_column,
+                            LineParseError::InvalidOrdinal,
+                        ));
+                        continue;
+                    }
+                }
+            }
+            key_lines.insert(record.key.clone(), record.line);
+            key_value_map.insert(record.key, value);
+        }
+
+        if parse_errors.line_errors.is_empty() {
+            Ok(DotEnvironmentConfigSource {
+                values: key_value_map,
+                warnings,
+                ordinal: config_ordinal.unwrap_or(295),
+                has_config_ordinal: config_ordinal.is_some(),
+                name: std::any::type_name::<DotEnvironmentConfigSource>()
+                    .split("::")
+                    .last()
+                    .unwrap()
+                    .to_string(),
+            })
+        } else {
+            Err(parse_errors)
+        }
+    }
 }
 
 impl ConfigSource for DotEnvironmentConfigSource {
     fn get_ordinal(&self) -> usize {
-        295
+        self.ordinal
     }
 
     fn get_value(&self, property_name: &str) -> Option<String> {
-        let key = convert_property_to_environment_name(property_name);
-        self.values.get(&key).map(|value| value.to_string())
+        self.get_value_ref(property_name).map(Cow::into_owned)
+    }
+
+    fn get_value_ref(&self, property_name: &str) -> Option<Cow<'_, str>> {
+        let key = cached_environment_name(property_name);
+        self.values
+            .get(&*key)
+            .map(|value| Cow::Borrowed(value.as_str()))
     }
 
     fn get_name(&self) -> &str {
-        std::any::type_name::<DotEnvironmentConfigSource>()
-            .split("::")
-            .last()
-            .unwrap()
+        &self.name
     }
 
     fn from_file(file_path: &str) -> Result<Self, FileError> {
-        match fs::read_to_string(file_path) {
-            Err(error) => Err(FileError::IoError(error)),
-            Ok(file_content) => match DotEnvironmentConfigSource::from_str(&file_content) {
-                Err(parse_errors) => Err(FileError::DotEnvLineParseErrors(parse_errors)),
-                Ok(config_source) => Ok(config_source),
-            },
-        }
+        DotEnvironmentConfigSource::from_file_with_options(file_path, &Default::default())
     }
 }
 
@@ -44,84 +258,401 @@ impl FromStr for DotEnvironmentConfigSource {
     type Err = DotEnvLineParseErrors;
 
     fn from_str(dot_env_str: &str) -> Result<Self, Self::Err> {
-        // Regular Expression splits text into records based on newlines while respecting multi-line quoted text
-        let result_key_value_pairs = Regex::new(r#"(?:[^\n]+"[^"]*"\n)|(?:[^\n]*\n)|(?:[^\n]+$)"#)
-            .unwrap()
-            .find_iter(dot_env_str)
-            .map(|m| m.as_str())
-            // enumerate records into line_no & record
-            .enumerate()
-            // Filter out records that should be skipped
-            .filter(|(_i, record)| {
-                let trimmed_record = record.trim();
-                let is_empty = trimmed_record.chars().count() == 0;
-                let is_comment = trimmed_record.starts_with("#");
-                !is_empty && !is_comment
-            })
-            // Map record into key value pairs
-            .map(|(line_no, record)| {
-                let tokens = record.split('=').collect::<Vec<&str>>();
-                if tokens.len() < 2 {
-                    Err((line_no, LineParseError::InvalidAssigment))
-                } else {
-                    let key = tokens[0]
-                        .trim()
-                        .trim_start_matches("export ")
-                        .trim()
-                        .to_uppercase();
-
-                    let temp_value = tokens[1].trim();
-                    let value: &str = if temp_value.starts_with("\"") && temp_value.ends_with("\"")
-                    {
-                        temp_value.trim_start_matches("\"").trim_end_matches("\"")
-                    } else {
-                        temp_value
-                    };
+        DotEnvironmentConfigSource::from_str_with_options(dot_env_str, &Default::default())
+    }
+}
 
-                    if key.is_empty() {
-                        Err((line_no, LineParseError::KeyIsEmpty))
-                    } else if value.is_empty() {
-                        Err((line_no, LineParseError::ValueIsEmpty))
-                    } else {
-                        Ok((key, value))
-                    }
+/// A single assignment parsed from a dotenv file
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct Record {
+    /// Physical line (1-indexed) the assignment starts on
+    pub(super) line: usize,
+    /// Column (1-indexed) of the key
+    pub(super) column: usize,
+    /// Column (1-indexed) where the value starts, after any whitespace following `=`
+    pub(super) value_column: usize,
+    pub(super) key: String,
+    pub(super) value: String,
+    /// The value before double quoted escapes are decoded, so interpolation can decode
+    /// them in the same pass and `\\$VAR` is not mistaken for an escaped `$`
+    pub(super) escaped_value: String,
+    /// The quote character surrounding the value, if any
+    pub(super) quote: Option<char>,
+    /// Byte range of the whole assignment, excluding the final line terminator
+    pub(super) span: Range<usize>,
+    /// Byte range of the value as written, including any quotes
+    pub(super) value_span: Range<usize>,
+}
+
+/// A parse error at a physical (line, column), both 1-indexed
+pub(super) type LineError = (usize, usize, LineParseError);
+
+/// Parses dotenv content into records in a single pass. Values may contain `=`, be
+/// single quoted (literal), double quoted (supporting `\n`, `\t`, `\"` and `\\`
+/// escapes) or backtick quoted (literal), and quoted values may span multiple lines.
+/// Unquoted values end at an inline `#` comment preceded by whitespace.
+pub(super) fn parse_records(dot_env_str: &str) -> Vec<Result<Record, LineError>> {
+    let mut lexer = Lexer {
+        content: dot_env_str,
+        offset: 0,
+        line: 1,
+        column: 1,
+    };
+    let mut records = Vec::new();
+    while let Some(record) = lexer.next_record() {
+        records.push(record);
+    }
+    records
+}
+
+/// A cursor over dotenv content tracking the physical line and column
+struct Lexer<'a> {
+    content: &'a str,
+    offset: usize,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> Lexer<'a> {
+    fn rest(&self) -> &'a str {
+        &self.content[self.offset..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let char = self.peek()?;
+        self.offset += char.len_utf8();
+        if char == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(char)
+    }
+
+    fn is_at_line_end(&self) -> bool {
+        let rest = self.rest();
+        rest.is_empty() || rest.starts_with('\n') || rest.starts_with("\r\n")
+    }
+
+    fn skip_inline_whitespace(&mut self) {
+        while self
+            .peek()
+            .is_some_and(|char| char != '\n' && char.is_whitespace())
+        {
+            self.bump();
+        }
+    }
+
+    /// Moves to the terminator of the current line, or the end of the content
+    fn skip_to_line_end(&mut self) {
+        while self.peek().is_some_and(|char| char != '\n') {
+            self.bump();
+        }
+    }
+
+    /// Offset of the current line end, excluding a `\r` before the terminator
+    fn line_end(&self, line_start: usize) -> usize {
+        match self.content[line_start..self.offset].ends_with('\r') {
+            true => self.offset - 1,
+            false => self.offset,
+        }
+    }
+
+    /// Reads the next assignment, skipping blank lines and comments, and leaves the
+    /// cursor at the start of the following line
+    fn next_record(&mut self) -> Option<Result<Record, LineError>> {
+        loop {
+            let line_start = self.offset;
+            self.skip_inline_whitespace();
+            match self.peek()? {
+                '\n' => {}
+                '#' => self.skip_to_line_end(),
+                _ => {
+                    let record = self.read_record(line_start);
+                    self.skip_to_line_end();
+                    self.bump();
+                    return Some(record);
                 }
-            })
-            .collect::<Vec<Result<(String, &str), (usize, LineParseError)>>>();
+            }
+            self.bump();
+        }
+    }
 
-        let mut parse_errors = DotEnvLineParseErrors {
-            line_errors: Vec::new(),
+    fn read_record(&mut self, line_start: usize) -> Result<Record, LineError> {
+        let line = self.line;
+        if let Some(after_export) = self.rest().strip_prefix("export") {
+            if after_export.starts_with(|char: char| char != '\n' && char.is_whitespace()) {
+                for _ in 0.."export".len() {
+                    self.bump();
+                }
+                self.skip_inline_whitespace();
+            }
+        }
+
+        let column = self.column;
+        let key_start = self.offset;
+        while self.peek().is_some_and(|char| char != '=' && char != '\n') {
+            self.bump();
+        }
+        if self.peek() != Some('=') {
+            return Err((line, column, LineParseError::InvalidAssigment));
+        }
+        let key = self.content[key_start..self.offset].trim().to_uppercase();
+        let equals_column = self.column;
+        self.bump();
+
+        let raw_value_start = self.offset;
+        self.skip_inline_whitespace();
+        let value_column = self.column;
+        let value_result = match self.peek() {
+            Some(quote @ ('"' | '\'' | '`')) => {
+                let value_start = self.offset;
+                self.read_quoted_value(quote)
+                    .map(|(value, escaped_value, value_end)| {
+                        (value, escaped_value, Some(quote), value_start..value_end)
+                    })
+            }
+            _ => {
+                self.skip_to_line_end();
+                let raw_value = &self.content[raw_value_start..self.line_end(raw_value_start)];
+                let value = strip_inline_comment(raw_value);
+                let value_start = offset_of(self.content, value);
+                Ok((
+                    value.to_string(),
+                    value.to_string(),
+                    None,
+                    value_start..value_start + value.len(),
+                ))
+            }
         };
-        let mut key_value_map: HashMap<String, String> = HashMap::new();
-        for result_pair in result_key_value_pairs {
-            if result_pair.is_err() {
-                parse_errors.line_errors.push(result_pair.err().unwrap());
-            } else {
-                let pair = result_pair.unwrap();
-                key_value_map.insert(pair.0.to_owned(), pair.1.to_owned());
+
+        let (value, escaped_value, quote, value_span) = value_result?;
+        if key.is_empty() {
+            return Err((line, equals_column, LineParseError::KeyIsEmpty));
+        }
+        self.skip_to_line_end();
+        Ok(Record {
+            line,
+            column,
+            value_column,
+            key,
+            value,
+            escaped_value,
+            quote,
+            span: line_start..self.line_end(line_start),
+            value_span,
+        })
+    }
+
+    /// Reads a quoted value starting at the opening quote, across as many lines as
+    /// needed. Returns the unescaped value, the value with its escapes kept and the
+    /// offset just after the closing quote.
+    fn read_quoted_value(&mut self, quote: char) -> Result<(String, String, usize), LineError> {
+        let (line, column) = (self.line, self.column);
+        self.bump();
+
+        let mut value = String::new();
+        let mut escaped_value = String::new();
+        loop {
+            match self.bump() {
+                None => return Err((line, column, LineParseError::UnterminatedQuote)),
+                Some(char) if char == quote => break,
+                // Line terminators inside values are normalized to `\n`
+                Some('\r') if self.peek() == Some('\n') => {}
+                Some('\\') if quote == '"' && !self.is_at_line_end() => {
+                    escaped_value.push('\\');
+                    match self.bump() {
+                        Some(escaped) => {
+                            escaped_value.push(escaped);
+                            match unescape(escaped) {
+                                Some(unescaped) => value.push(unescaped),
+                                None => {
+                                    value.push('\\');
+                                    value.push(escaped);
+                                }
+                            }
+                        }
+                        None => value.push('\\'),
+                    }
+                }
+                Some(char) => {
+                    value.push(char);
+                    escaped_value.push(char);
+                }
             }
         }
+        let value_end = self.offset;
 
-        if parse_errors.line_errors.is_empty() {
-            Ok(DotEnvironmentConfigSource {
-                values: key_value_map,
+        // Only whitespace or a comment may follow the closing quote
+        self.skip_inline_whitespace();
+        if self.is_at_line_end() || self.peek() == Some('#') {
+            Ok((value, escaped_value, value_end))
+        } else {
+            Err((self.line, self.column, LineParseError::UnexpectedCharacters))
+        }
+    }
+}
+
+/// The character a double quoted `\\{escaped}` sequence stands for
+fn unescape(escaped: char) -> Option<char> {
+    match escaped {
+        'n' => Some('\n'),
+        't' => Some('\t'),
+        'r' => Some('\r'),
+        '"' | '\\' => Some(escaped),
+        _ => None,
+    }
+}
+
+/// Byte offset of `slice` within `content`, which it must have been sliced from
+fn offset_of(content: &str, slice: &str) -> usize {
+    slice.as_ptr() as usize - content.as_ptr() as usize
+}
+
+/// Removes a trailing `# comment` from an unquoted value. A `#` only starts a comment
+/// when it is preceded by whitespace so values like `color=#fff` are kept intact.
+fn strip_inline_comment(raw_value: &str) -> &str {
+    let mut previous = '=';
+    for (position, char) in raw_value.char_indices() {
+        if char == '#' && previous.is_whitespace() {
+            return raw_value[..position].trim();
+        }
+        previous = char;
+    }
+    raw_value.trim()
+}
+
+/// Expands `${VAR}`, `$VAR` and `${VAR:-default}` references using keys defined
+/// earlier in the file, falling back to the process environment when enabled.
+/// Unresolved references expand to an empty string and `\$` produces a literal `$`.
+/// Double quoted values are passed with their escapes kept and decoded here.
+fn interpolate(
+    value: &str,
+    double_quoted: bool,
+    defined_values: &HashMap<String, String>,
+    options: &DotEnvParseOptions,
+) -> String {
+    let lookup = |name: &str| {
+        defined_values
+            .get(&convert_property_to_environment_name(name))
+            .cloned()
+            .or_else(|| {
+                options
+                    .interpolate_environment
+                    .then(|| env::var(name).ok())
+                    .flatten()
             })
+    };
+
+    let mut result = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(position) = rest.find(['$', '\\']) {
+        result.push_str(&rest[..position]);
+        let reference = &rest[position..];
+
+        if let Some(escaped) = reference.strip_prefix("\\$") {
+            result.push('$');
+            rest = escaped;
+        } else if let Some(after_backslash) = reference.strip_prefix('\\') {
+            match after_backslash.chars().next().and_then(unescape) {
+                Some(unescaped) if double_quoted => {
+                    result.push(unescaped);
+                    rest = &after_backslash[1..];
+                }
+                _ => {
+                    result.push('\\');
+                    rest = after_backslash;
+                }
+            }
+        } else if let Some(braced) = reference.strip_prefix("${") {
+            match braced.find('}') {
+                Some(end) => {
+                    let expression = &braced[..end];
+                    let resolved = match expression.split_once(":-") {
+                        Some((name, default)) => lookup(name)
+                            .filter(|value| !value.is_empty())
+                            .unwrap_or(default.to_string()),
+                        None => lookup(expression).unwrap_or_default(),
+                    };
+                    result.push_str(&resolved);
+                    rest = &braced[end + 1..];
+                }
+                None => {
+                    // Unterminated references are kept as written
+                    result.push_str(reference);
+                    rest = "";
+                }
+            }
         } else {
-            Err(parse_errors)
+            let name_length = reference[1..]
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(reference.len() - 1);
+            if name_length == 0 {
+                result.push('$');
+            } else {
+                result.push_str(&lookup(&reference[1..=name_length]).unwrap_or_default());
+            }
+            rest = &reference[1 + name_length..];
         }
     }
+    result.push_str(rest);
+    result
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct DotEnvLineParseErrors {
-    line_errors: Vec<(usize, LineParseError)>,
+    pub(super) line_errors: Vec<LineError>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
-enum LineParseError {
+pub(super) enum LineParseError {
     InvalidAssigment,
     KeyIsEmpty,
     ValueIsEmpty,
+    UnterminatedQuote,
+    UnexpectedCharacters,
+    DuplicateKey,
+    InvalidOrdinal,
+}
+
+impl LineParseError {
+    fn description(&self) -> &'static str {
+        match self {
+            LineParseError::InvalidAssigment => "Record has invalid '=' operand",
+            LineParseError::KeyIsEmpty => "key is empty",
+            LineParseError::ValueIsEmpty => "value is empty",
+            LineParseError::UnterminatedQuote => "quoted value is never closed",
+            LineParseError::UnexpectedCharacters => "unexpected characters after quoted value",
+            LineParseError::DuplicateKey => "key is already defined",
+            LineParseError::InvalidOrdinal => "CONFIG_ORDINAL must be a non-negative integer",
+        }
+    }
+
+    #[cfg(feature = "diagnostics")]
+    fn help(&self) -> &'static str {
+        match self {
+            LineParseError::InvalidAssigment => "assignments are written as KEY=value",
+            LineParseError::KeyIsEmpty => "add a key before the '='",
+            LineParseError::ValueIsEmpty => {
+                "set a value, or allow empty values with an EmptyValuePolicy"
+            }
+            LineParseError::UnterminatedQuote => {
+                "close the value with the same quote it opens with"
+            }
+            LineParseError::UnexpectedCharacters => {
+                "comments after a quoted value must start with ' #'"
+            }
+            LineParseError::DuplicateKey => {
+                "remove one of the assignments, or allow them with a DuplicateKeyPolicy"
+            }
+            LineParseError::InvalidOrdinal => "set CONFIG_ORDINAL to a whole number such as 250",
+        }
+    }
 }
 
 impl fmt::Display for DotEnvLineParseErrors {
@@ -131,18 +662,37 @@ impl fmt::Display for DotEnvLineParseErrors {
             "Found {} errors while parsing Dot Environment File",
             self.line_errors.len()
         )?;
-        for (line_number, parse_error) in self.line_errors.iter() {
-            let error_description = match parse_error {
-                LineParseError::InvalidAssigment => "Record has invalid '=' operand",
-                LineParseError::KeyIsEmpty => "key is empty",
-                LineParseError::ValueIsEmpty => "value is empty",
-            };
-            writeln!(f, "Line {}: {}", line_number, error_description)?;
+        for (line_number, column, parse_error) in self.line_errors.iter() {
+            writeln!(
+                f,
+                "Line {}, column {}: {}",
+                line_number,
+                column,
+                parse_error.description()
+            )?;
         }
         Ok(())
     }
 }
 
+#[cfg(feature = "diagnostics")]
+impl DotEnvLineParseErrors {
+    /// One diagnostic per failing line of `file_name`
+    pub fn diagnostics(&self, file_name: &str) -> Vec<Diagnostic> {
+        self.line_errors
+            .iter()
+            .map(|(line, column, parse_error)| Diagnostic {
+                file_name: file_name.to_owned(),
+                location: Some((*line, *column)),
+                message: parse_error.description().to_owned(),
+                help: Some(parse_error.help()),
+            })
+            .collect()
+    }
+}
+
+impl Error for DotEnvLineParseErrors {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -241,7 +791,7 @@ mod tests {
         let parse_errors = dot_env_source_result.err();
 
         let expected_parse_errors = DotEnvLineParseErrors {
-            line_errors: vec![(2, LineParseError::ValueIsEmpty)],
+            line_errors: vec![(3, 17, LineParseError::ValueIsEmpty)],
         };
         assert_eq!(parse_errors, Some(expected_parse_errors));
     }
@@ -262,8 +812,8 @@ mod tests {
 
         let expected_parse_errors = DotEnvLineParseErrors {
             line_errors: vec![
-                (2, LineParseError::ValueIsEmpty),
-                (4, LineParseError::KeyIsEmpty),
+                (3, 17, LineParseError::ValueIsEmpty),
+                (5, 9, LineParseError::KeyIsEmpty),
             ],
         };
         assert_eq!(parse_errors, Some(expected_parse_errors));
@@ -294,6 +844,401 @@ mod tests {
         )
     }
 
+    #[test]
+    fn keys_are_stored_by_environment_name() {
+        let dot_env_str = "MY-KEY=1\nother.key=2\nINTERPOLATED=${my-key}";
+
+        let dot_env_source = DotEnvironmentConfigSource::from_str(dot_env_str).unwrap();
+        assert_eq!(dot_env_source.get_value("my-key"), Some("1".to_string()));
+        assert_eq!(dot_env_source.get_value("MY-KEY"), Some("1".to_string()));
+        assert_eq!(dot_env_source.get_value("my.key"), Some("1".to_string()));
+        assert_eq!(dot_env_source.get_value("other.key"), Some("2".to_string()));
+        assert_eq!(
+            dot_env_source.get_value("interpolated"),
+            Some("1".to_string())
+        );
+    }
+
+    #[test]
+    fn parse_values_containing_equals() {
+        let dot_env_str = "
+        URL=postgres://u:p@h/db?sslmode=require
+        QUOTED_URL=\"a=b=c\"
+        ";
+
+        let dot_env_source = DotEnvironmentConfigSource::from_str(dot_env_str).unwrap();
+        assert_eq!(
+            get_config_value(&dot_env_source, "URL"),
+            Some("postgres://u:p@h/db?sslmode=require".to_string())
+        );
+        assert_eq!(
+            get_config_value(&dot_env_source, "QUOTED_URL"),
+            Some("a=b=c".to_string())
+        );
+    }
+
+    #[test]
+    fn parse_quoting_styles() {
+        let dot_env_str = r#"
+        SINGLE='literal \n $value # not a comment'
+        DOUBLE="line one\nline two\ttabbed \"quoted\" back\\slash"
+        BACKTICK=`it's "mixed" quotes`
+        "#;
+
+        let dot_env_source = DotEnvironmentConfigSource::from_str(dot_env_str).unwrap();
+        assert_eq!(
+            get_config_value(&dot_env_source, "SINGLE"),
+            Some("literal \\n $value # not a comment".to_string())
+        );
+        assert_eq!(
+            get_config_value(&dot_env_source, "DOUBLE"),
+            Some("line one\nline two\ttabbed \"quoted\" back\\slash".to_string())
+        );
+        assert_eq!(
+            get_config_value(&dot_env_source, "BACKTICK"),
+            Some("it's \"mixed\" quotes".to_string())
+        );
+    }
+
+    #[test]
+    fn parse_inline_comments() {
+        let dot_env_str = "
+        UNQUOTED=value # trailing comment
+        COLOR=#fff
+        HASH=abc#def
+        QUOTED=\"value # kept\" # trailing comment
+        ";
+
+        let dot_env_source = DotEnvironmentConfigSource::from_str(dot_env_str).unwrap();
+        assert_eq!(
+            get_config_value(&dot_env_source, "UNQUOTED"),
+            Some("value".to_string())
+        );
+        assert_eq!(
+            get_config_value(&dot_env_source, "COLOR"),
+            Some("#fff".to_string())
+        );
+        assert_eq!(
+            get_config_value(&dot_env_source, "HASH"),
+            Some("abc#def".to_string())
+        );
+        assert_eq!(
+            get_config_value(&dot_env_source, "QUOTED"),
+            Some("value # kept".to_string())
+        );
+    }
+
+    #[test]
+    fn error_lines_are_physical_lines() {
+        let dot_env_str = "FIRST=\"spans
+multiple
+lines\"
+MISSING_ASSIGNMENT
+SECOND='never closed
+THIRD=ok";
+
+        let parse_errors = DotEnvironmentConfigSource::from_str(dot_env_str).err();
+
+        let expected_parse_errors = DotEnvLineParseErrors {
+            line_errors: vec![
+                (4, 1, LineParseError::InvalidAssigment),
+                (5, 8, LineParseError::UnterminatedQuote),
+            ],
+        };
+        assert_eq!(parse_errors, Some(expected_parse_errors));
+    }
+
+    #[test]
+    fn error_on_characters_after_closing_quote() {
+        let dot_env_str = "KEY=\"value\" trailing";
+
+        let parse_errors = DotEnvironmentConfigSource::from_str(dot_env_str).err();
+        let expected_parse_errors = DotEnvLineParseErrors {
+            line_errors: vec![(1, 13, LineParseError::UnexpectedCharacters)],
+        };
+        assert_eq!(parse_errors, Some(expected_parse_errors));
+    }
+
+    #[test]
+    fn error_columns_follow_multi_line_values() {
+        let dot_env_str = "\
+FIRST=\"spans
+two\" trailing
+  ÜBER = 'ok'
+  export   =blah
+";
+
+        let parse_errors = DotEnvironmentConfigSource::from_str(dot_env_str).err();
+        let expected_parse_errors = DotEnvLineParseErrors {
+            line_errors: vec![
+                (2, 6, LineParseError::UnexpectedCharacters),
+                (4, 12, LineParseError::KeyIsEmpty),
+            ],
+        };
+        assert_eq!(parse_errors, Some(expected_parse_errors));
+    }
+
+    #[test]
+    fn config_ordinal_sets_the_ordinal() {
+        let config_source = DotEnvironmentConfigSource::from_str("CONFIG_ORDINAL=120").unwrap();
+        assert_eq!(config_source.get_ordinal(), 120);
+        assert_eq!(config_source.with_default_ordinal(295).get_ordinal(), 120);
+
+        let parse_errors =
+            DotEnvironmentConfigSource::from_str("KEY=value\nCONFIG_ORDINAL=-1").err();
+        assert_eq!(
+            parse_errors,
+            Some(DotEnvLineParseErrors {
+                line_errors: vec![(2, 16, LineParseError::InvalidOrdinal)],
+            })
+        );
+    }
+
+    #[test]
+    fn record_positions_and_spans() {
+        let dot_env_str = "  export KEY = \"a\r\nb\" # note\r\nÄ=ü\r\n";
+        let records: Vec<Record> = parse_records(dot_env_str)
+            .into_iter()
+            .map(Result::unwrap)
+            .collect();
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(
+            (records[0].line, records[0].column, records[0].value_column),
+            (1, 10, 16)
+        );
+        assert_eq!(records[0].value, "a\nb");
+        assert_eq!(
+            &dot_env_str[records[0].span.clone()],
+            "  export KEY = \"a\r\nb\" # note"
+        );
+        assert_eq!(&dot_env_str[records[0].value_span.clone()], "\"a\r\nb\"");
+
+        assert_eq!(
+            (records[1].line, records[1].column, records[1].value_column),
+            (3, 1, 3)
+        );
+        assert_eq!(&dot_env_str[records[1].span.clone()], "Ä=ü");
+    }
+
+    #[test]
+    fn parse_windows_line_endings() {
+        let dot_env_str = "FIRST=one\r\nSECOND=\"two\"\r\n";
+
+        let dot_env_source = DotEnvironmentConfigSource::from_str(dot_env_str).unwrap();
+        assert_eq!(
+            get_config_value(&dot_env_source, "FIRST"),
+            Some("one".to_string())
+        );
+        assert_eq!(
+            get_config_value(&dot_env_source, "SECOND"),
+            Some("two".to_string())
+        );
+    }
+
+    #[test]
+    fn interpolate_earlier_keys() {
+        let dot_env_str = r#"
+        DB_HOST=db.internal
+        DB_PORT=5432
+        DB_URL=postgres://${DB_HOST}:$DB_PORT/app
+        QUOTED_URL="${db_host}/quoted"
+        LITERAL='${DB_HOST}'
+        BACKTICK=`$DB_HOST`
+        ESCAPED=\$DB_HOST
+        PRICE=5$
+        "#;
+
+        let dot_env_source = DotEnvironmentConfigSource::from_str(dot_env_str).unwrap();
+        assert_eq!(
+            get_config_value(&dot_env_source, "DB_URL"),
+            Some("postgres://db.internal:5432/app".to_string())
+        );
+        assert_eq!(
+            get_config_value(&dot_env_source, "QUOTED_URL"),
+            Some("db.internal/quoted".to_string())
+        );
+        assert_eq!(
+            get_config_value(&dot_env_source, "LITERAL"),
+            Some("${DB_HOST}".to_string())
+        );
+        assert_eq!(
+            get_config_value(&dot_env_source, "BACKTICK"),
+            Some("$DB_HOST".to_string())
+        );
+        assert_eq!(
+            get_config_value(&dot_env_source, "ESCAPED"),
+            Some("$DB_HOST".to_string())
+        );
+        assert_eq!(
+            get_config_value(&dot_env_source, "PRICE"),
+            Some("5$".to_string())
+        );
+    }
+
+    #[test]
+    fn interpolate_decodes_each_escape_once() {
+        let dot_env_str = r#"
+        HOME_DIR=/home/app
+        ESCAPED_BACKSLASH="x\\$HOME_DIR"
+        ESCAPED_DOLLAR="x\$HOME_DIR"
+        ESCAPED_QUOTE="\"$HOME_DIR\"\tend"
+        UNQUOTED=x\\$HOME_DIR
+        "#;
+
+        let dot_env_source = DotEnvironmentConfigSource::from_str(dot_env_str).unwrap();
+        assert_eq!(
+            get_config_value(&dot_env_source, "ESCAPED_BACKSLASH"),
+            Some("x\\/home/app".to_string())
+        );
+        assert_eq!(
+            get_config_value(&dot_env_source, "ESCAPED_DOLLAR"),
+            Some("x$HOME_DIR".to_string())
+        );
+        assert_eq!(
+            get_config_value(&dot_env_source, "ESCAPED_QUOTE"),
+            Some("\"/home/app\"\tend".to_string())
+        );
+        assert_eq!(
+            get_config_value(&dot_env_source, "UNQUOTED"),
+            Some("x\\$HOME_DIR".to_string())
+        );
+    }
+
+    #[test]
+    fn interpolate_defaults_and_later_keys() {
+        let dot_env_str = r#"
+        EARLY=${LATER:-fallback}
+        MISSING=before${NOT_DEFINED}after
+        LATER=late value
+        "#;
+
+        let dot_env_source = DotEnvironmentConfigSource::from_str(dot_env_str).unwrap();
+        assert_eq!(
+            get_config_value(&dot_env_source, "EARLY"),
+            Some("fallback".to_string())
+        );
+        assert_eq!(
+            get_config_value(&dot_env_source, "MISSING"),
+            Some("beforeafter".to_string())
+        );
+    }
+
+    #[test]
+    fn interpolate_process_environment() {
+        env::set_var("DOT_ENV_INTERPOLATION_HOST", "env.host");
+        let dot_env_str = "URL=https://${DOT_ENV_INTERPOLATION_HOST:-localhost}/";
+
+        let without_env = DotEnvironmentConfigSource::from_str(dot_env_str).unwrap();
+        let with_env = DotEnvironmentConfigSource::from_str_with_options(
+            dot_env_str,
+            &DotEnvParseOptions {
+                interpolate_environment: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        env::remove_var("DOT_ENV_INTERPOLATION_HOST");
+
+        assert_eq!(
+            get_config_value(&without_env, "URL"),
+            Some("https://localhost/".to_string())
+        );
+        assert_eq!(
+            get_config_value(&with_env, "URL"),
+            Some("https://env.host/".to_string())
+        );
+    }
+
+    #[test]
+    fn empty_value_policies() {
+        let dot_env_str = "
+        FIRST=one
+        EMPTY=
+        QUOTED_EMPTY=\"\"
+        MISSING=${NOT_DEFINED}
+        ";
+        let parse_with = |empty_values| {
+            DotEnvironmentConfigSource::from_str_with_options(
+                dot_env_str,
+                &DotEnvParseOptions {
+                    empty_values,
+                    ..Default::default()
+                },
+            )
+        };
+
+        assert_eq!(
+            parse_with(EmptyValuePolicy::Error).err(),
+            Some(DotEnvLineParseErrors {
+                line_errors: vec![
+                    (3, 15, LineParseError::ValueIsEmpty),
+                    (4, 22, LineParseError::ValueIsEmpty),
+                    (5, 17, LineParseError::ValueIsEmpty),
+                ],
+            })
+        );
+
+        let empty_string_source = parse_with(EmptyValuePolicy::EmptyString).unwrap();
+        assert_eq!(empty_string_source.get_value("empty"), Some(String::new()));
+        assert_eq!(
+            empty_string_source.get_value("quoted.empty"),
+            Some(String::new())
+        );
+
+        let unset_source = parse_with(EmptyValuePolicy::Unset).unwrap();
+        assert_eq!(unset_source.get_value("empty"), None);
+        assert_eq!(unset_source.get_value("quoted.empty"), None);
+        assert_eq!(unset_source.get_value("missing"), None);
+        assert_eq!(unset_source.get_value("first"), Some("one".to_string()));
+    }
+
+    #[test]
+    fn duplicate_key_policies() {
+        let dot_env_str = "
+        KEY=first
+        OTHER=value
+        key=second
+        ";
+        let parse_with = |duplicate_keys| {
+            DotEnvironmentConfigSource::from_str_with_options(
+                dot_env_str,
+                &DotEnvParseOptions {
+                    duplicate_keys,
+                    ..Default::default()
+                },
+            )
+        };
+
+        assert_eq!(
+            parse_with(DuplicateKeyPolicy::Error).err(),
+            Some(DotEnvLineParseErrors {
+                line_errors: vec![(4, 9, LineParseError::DuplicateKey)],
+            })
+        );
+
+        let first_wins_source = parse_with(DuplicateKeyPolicy::FirstWins).unwrap();
+        assert_eq!(
+            first_wins_source.get_value("key"),
+            Some("first".to_string())
+        );
+        assert!(first_wins_source.warnings().is_empty());
+
+        let last_wins_source = parse_with(DuplicateKeyPolicy::LastWinsWithWarning).unwrap();
+        assert_eq!(
+            last_wins_source.get_value("key"),
+            Some("second".to_string())
+        );
+        assert_eq!(
+            last_wins_source.warnings(),
+            &[DotEnvWarning::DuplicateKey {
+                key: "KEY".to_string(),
+                line: 4,
+                previous_line: 2,
+            }]
+        );
+    }
+
     #[test]
     fn parse_config_without_newlines() {
         let dot_env_str = "FIRST=one";
@@ -329,7 +1274,7 @@ mod tests {
         let dot_config_result = DotEnvironmentConfigSource::from_file("./fake-file.env");
         assert!(dot_config_result.is_err());
         let config_error = dot_config_result.err().unwrap();
-        assert!(matches!(config_error, FileError::IoError(_)));
+        assert!(matches!(config_error, FileError::IoError { .. }));
     }
 
     #[test]
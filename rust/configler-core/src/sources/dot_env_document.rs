@@ -0,0 +1,405 @@
+use core::fmt;
+use std::{fs, str::FromStr};
+
+use super::{
+    config_source::{convert_property_to_environment_name, FileError},
+    dot_env::{parse_records, DotEnvLineParseErrors},
+};
+
+/// A lossless model of a dotenv file used to edit it programmatically.
+///
+/// Comments, blank lines, ordering, `export` prefixes, spacing and quoting are kept
+/// exactly as written, so serializing an unmodified document reproduces the original
+/// content byte-for-byte and edits only touch the entries they change.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DotEnvDocument {
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    /// Comments, blank lines and line terminators between entries
+    Trivia(String),
+    Entry(Entry),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Entry {
+    key: String,
+    value: String,
+    quote: Option<char>,
+    /// Everything before the value, e.g. `export KEY = `
+    prefix: String,
+    /// The value as written, including any quotes
+    raw_value: String,
+    /// Everything after the value on its last line, e.g. ` # comment`
+    suffix: String,
+}
+
+impl DotEnvDocument {
+    pub fn from_file(file_path: &str) -> Result<Self, FileError> {
+        match fs::read_to_string(file_path) {
+            Err(error) => Err(FileError::IoError {
+                path: file_path.to_owned(),
+                error,
+            }),
+            Ok(file_content) => DotEnvDocument::from_str(&file_content).map_err(|errors| {
+                FileError::DotEnvLineParseErrors {
+                    path: file_path.to_owned(),
+                    errors,
+                }
+            }),
+        }
+    }
+
+    pub fn write_to_file(&self, file_path: &str) -> Result<(), FileError> {
+        fs::write(file_path, self.to_string()).map_err(|error| FileError::IoError {
+            path: file_path.to_owned(),
+            error,
+        })
+    }
+
+    /// Returns the unescaped value of the last assignment of `key`
+    pub fn get(&self, key: &str) -> Option<&str> {
+        let key = convert_property_to_environment_name(key);
+        self.entries()
+            .filter(|entry| entry.key == key)
+            .last()
+            .map(|entry| entry.value.as_str())
+    }
+
+    /// Keys in the order they are first assigned
+    pub fn keys(&self) -> Vec<&str> {
+        let mut keys: Vec<&str> = Vec::new();
+        for entry in self.entries() {
+            if !keys.contains(&entry.key.as_str()) {
+                keys.push(&entry.key);
+            }
+        }
+        keys
+    }
+
+    /// Sets the value of `key`. An existing assignment is rewritten in place keeping
+    /// its prefix, trailing comment and, where the value allows it, its quote style.
+    /// New keys are appended to the end of the document.
+    pub fn set(&mut self, key: &str, value: &str) {
+        let environment_key = convert_property_to_environment_name(key);
+        let existing_entry = self
+            .segments
+            .iter_mut()
+            .rev()
+            .find_map(|segment| match segment {
+                Segment::Entry(entry) if entry.key == environment_key => Some(entry),
+                _ => None,
+            });
+
+        match existing_entry {
+            Some(entry) => {
+                let quote = choose_quote(value, entry.quote);
+                entry.raw_value = encode_value(value, quote);
+                entry.value = value.to_owned();
+                entry.quote = quote;
+            }
+            None => {
+                if let Some(Segment::Trivia(trivia)) = self.segments.last_mut() {
+                    if !trivia.ends_with('\n') {
+                        trivia.push('\n');
+                    }
+                } else if !self.segments.is_empty() {
+                    self.segments.push(Segment::Trivia("\n".to_string()));
+                }
+
+                let quote = choose_quote(value, None);
+                self.segments.push(Segment::Entry(Entry {
+                    key: environment_key,
+                    value: value.to_owned(),
+                    quote,
+                    prefix: format!("{}=", key),
+                    raw_value: encode_value(value, quote),
+                    suffix: String::new(),
+                }));
+                self.segments.push(Segment::Trivia("\n".to_string()));
+            }
+        }
+    }
+
+    /// Removes every assignment of `key` along with its line terminator, returning
+    /// whether anything was removed
+    pub fn unset(&mut self, key: &str) -> bool {
+        let key = convert_property_to_environment_name(key);
+        let mut removed = false;
+        let mut index = 0;
+        while index < self.segments.len() {
+            match &self.segments[index] {
+                Segment::Entry(entry) if entry.key == key => {
+                    self.segments.remove(index);
+                    if let Some(Segment::Trivia(trivia)) = self.segments.get_mut(index) {
+                        let terminator = if trivia.starts_with("\r\n") { 2 } else { 1 };
+                        if trivia.starts_with('\n') || trivia.starts_with("\r\n") {
+                            trivia.replace_range(..terminator, "");
+                        }
+                    }
+                    removed = true;
+                }
+                _ => index += 1,
+            }
+        }
+        removed
+    }
+
+    fn entries(&self) -> impl Iterator<Item = &Entry> {
+        self.segments.iter().filter_map(|segment| match segment {
+            Segment::Entry(entry) => Some(entry),
+            Segment::Trivia(_) => None,
+        })
+    }
+}
+
+impl FromStr for DotEnvDocument {
+    type Err = DotEnvLineParseErrors;
+
+    fn from_str(dot_env_str: &str) -> Result<Self, Self::Err> {
+        let mut segments = Vec::new();
+        let mut line_errors = Vec::new();
+        let mut position = 0;
+
+        for result_record in parse_records(dot_env_str) {
+            let record = match result_record {
+                Err(line_error) => {
+                    line_errors.push(line_error);
+                    continue;
+                }
+                Ok(record) => record,
+            };
+
+            if record.span.start > position {
+                segments.push(Segment::Trivia(
+                    dot_env_str[position..record.span.start].to_string(),
+                ));
+            }
+            // Keys are matched by environment name, the same way the dotenv source
+            // looks them up
+            segments.push(Segment::Entry(Entry {
+                key: convert_property_to_environment_name(&record.key),
+                value: record.value,
+                quote: record.quote,
+                prefix: dot_env_str[record.span.start..record.value_span.start].to_string(),
+                raw_value: dot_env_str[record.value_span.clone()].to_string(),
+                suffix: dot_env_str[record.value_span.end..record.span.end].to_string(),
+            }));
+            position = record.span.end;
+        }
+
+        if position < dot_env_str.len() {
+            segments.push(Segment::Trivia(dot_env_str[position..].to_string()));
+        }
+
+        if line_errors.is_empty() {
+            Ok(DotEnvDocument { segments })
+        } else {
+            Err(DotEnvLineParseErrors { line_errors })
+        }
+    }
+}
+
+impl fmt::Display for DotEnvDocument {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for segment in self.segments.iter() {
+            match segment {
+                Segment::Trivia(trivia) => write!(f, "{}", trivia)?,
+                Segment::Entry(entry) => {
+                    write!(f, "{}{}{}", entry.prefix, entry.raw_value, entry.suffix)?
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Keeps the current quote style when it can represent the value, otherwise picks
+/// double quotes for values that would not survive unquoted
+fn choose_quote(value: &str, current: Option<char>) -> Option<char> {
+    match current {
+        Some('"') => Some('"'),
+        Some(quote) if !value.contains(quote) => Some(quote),
+        None if !needs_quotes(value) => None,
+        _ => Some('"'),
+    }
+}
+
+fn needs_quotes(value: &str) -> bool {
+    value.is_empty()
+        || value.trim() != value
+        || value.contains(['\n', '\r', '\t', '"', '\'', '`', '\\', '#', '$'])
+}
+
+fn encode_value(value: &str, quote: Option<char>) -> String {
+    match quote {
+        Some('"') => {
+            let mut encoded = String::from('"');
+            for char in value.chars() {
+                match char {
+                    '"' => encoded.push_str("\\\""),
+                    '\\' => encoded.push_str("\\\\"),
+                    // Double quoted values are interpolated so references must be escaped
+                    '$' => encoded.push_str("\\$"),
+                    '\n' => encoded.push_str("\\n"),
+                    '\t' => encoded.push_str("\\t"),
+                    '\r' => encoded.push_str("\\r"),
+                    other => encoded.push(other),
+                }
+            }
+            encoded.push('"');
+            encoded
+        }
+        Some(quote) => format!("{}{}{}", quote, value, quote),
+        None => value.to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::{dot_env::DotEnvironmentConfigSource, ConfigSource};
+
+    const DOT_ENV: &str = "# Database settings
+export DB_HOST = db.internal # primary
+DB_USER='admin'
+DB_PASSWORD=\"multi
+line\"
+
+# Feature flags
+FEATURE=on
+";
+
+    #[test]
+    fn round_trip_is_lossless() {
+        let document = DotEnvDocument::from_str(DOT_ENV).unwrap();
+        assert_eq!(document.to_string(), DOT_ENV);
+
+        let without_trailing_newline = "A=1\r\n  # comment\r\nB=\"two\"";
+        let document = DotEnvDocument::from_str(without_trailing_newline).unwrap();
+        assert_eq!(document.to_string(), without_trailing_newline);
+    }
+
+    #[test]
+    fn read_entries() {
+        let document = DotEnvDocument::from_str(DOT_ENV).unwrap();
+
+        assert_eq!(document.get("db_host"), Some("db.internal"));
+        assert_eq!(document.get("DB_PASSWORD"), Some("multi\nline"));
+        assert_eq!(document.get("missing"), None);
+        assert_eq!(
+            document.keys(),
+            vec!["DB_HOST", "DB_USER", "DB_PASSWORD", "FEATURE"]
+        );
+    }
+
+    #[test]
+    fn set_existing_keeps_layout() {
+        let mut document = DotEnvDocument::from_str(DOT_ENV).unwrap();
+        document.set("DB_HOST", "db.replica");
+        document.set("DB_USER", "root");
+        document.set("DB_PASSWORD", "single line");
+
+        assert_eq!(
+            document.to_string(),
+            "# Database settings
+export DB_HOST = db.replica # primary
+DB_USER='root'
+DB_PASSWORD=\"single line\"
+
+# Feature flags
+FEATURE=on
+"
+        );
+    }
+
+    #[test]
+    fn set_quotes_values_when_needed() {
+        let mut document = DotEnvDocument::from_str("PLAIN=value\nSINGLE='value'\n").unwrap();
+        document.set("PLAIN", "has # hash");
+        document.set("SINGLE", "it's");
+        document.set("PRICE", "$5");
+
+        assert_eq!(
+            document.to_string(),
+            "PLAIN=\"has # hash\"\nSINGLE=\"it's\"\nPRICE=\"\\$5\"\n"
+        );
+
+        let reparsed = DotEnvironmentConfigSource::from_str(&document.to_string()).unwrap();
+        assert_eq!(reparsed.get_value("PLAIN"), Some("has # hash".to_string()));
+        assert_eq!(reparsed.get_value("SINGLE"), Some("it's".to_string()));
+        assert_eq!(reparsed.get_value("PRICE"), Some("$5".to_string()));
+    }
+
+    #[test]
+    fn set_appends_new_keys() {
+        let mut document = DotEnvDocument::from_str("# comment\nA=1").unwrap();
+        document.set("New_Key", "two words");
+
+        assert_eq!(document.to_string(), "# comment\nA=1\nNew_Key=two words\n");
+
+        let mut empty_document = DotEnvDocument::from_str("").unwrap();
+        empty_document.set("A", "1");
+        assert_eq!(empty_document.to_string(), "A=1\n");
+    }
+
+    #[test]
+    fn unset_removes_entry_lines() {
+        let mut document = DotEnvDocument::from_str(DOT_ENV).unwrap();
+        assert!(document.unset("DB_PASSWORD"));
+        assert!(document.unset("feature"));
+        assert!(!document.unset("missing"));
+
+        assert_eq!(
+            document.to_string(),
+            "# Database settings
+export DB_HOST = db.internal # primary
+DB_USER='admin'
+
+# Feature flags
+"
+        );
+    }
+
+    #[test]
+    fn keys_are_matched_by_environment_name() {
+        let mut document = DotEnvDocument::from_str("MY-KEY=1\nDB_HOST=db\n").unwrap();
+        assert_eq!(document.get("my-key"), Some("1"));
+        assert_eq!(document.get("db.host"), Some("db"));
+
+        document.set("my.key", "2");
+        assert_eq!(document.to_string(), "MY-KEY=2\nDB_HOST=db\n");
+
+        let reparsed = DotEnvironmentConfigSource::from_str(&document.to_string()).unwrap();
+        assert_eq!(reparsed.get_value("my-key"), Some("2".to_string()));
+
+        assert!(document.unset("db-host"));
+        assert_eq!(document.to_string(), "MY-KEY=2\n");
+    }
+
+    #[test]
+    fn invalid_documents_report_line_errors() {
+        let result = DotEnvDocument::from_str("A=1\nnot an assignment\n");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn edit_file_round_trip() {
+        let file_path = std::env::temp_dir().join(format!(
+            "configler_dot_env_document_{}_edit_file_round_trip.env",
+            std::process::id()
+        ));
+        let file_path = file_path.to_str().unwrap();
+        fs::write(file_path, DOT_ENV).unwrap();
+
+        let mut document = DotEnvDocument::from_file(file_path).unwrap();
+        document.set("FEATURE", "off");
+        document.write_to_file(file_path).unwrap();
+
+        let written = fs::read_to_string(file_path).unwrap();
+        fs::remove_file(file_path).unwrap();
+        assert_eq!(written, DOT_ENV.replace("FEATURE=on", "FEATURE=off"));
+    }
+}
@@ -0,0 +1,277 @@
+use std::{fs, path::Path};
+
+use regex::Regex;
+
+use crate::PrecedencePolicy;
+
+use super::{
+    config_source::FileError,
+    dot_env::{DotEnvParseOptions, DotEnvironmentConfigSource},
+    yaml::YamlParseOptions,
+    ConfigSource, YamlConfigSource,
+};
+
+/// A `conf.d` style directory where every supported file becomes its own config source.
+///
+/// Files are read in lexical order of their file names and later files (e.g.
+/// `90-local.env`) override earlier ones (e.g. `10-base.yaml`). Every file shares the
+/// directory's ordinal, by default the [`drop_in_ordinal`](PrecedencePolicy::drop_in_ordinal)
+/// of the builder's [`PrecedencePolicy`], so a directory ranks against other sources
+/// the same way however many files it holds.
+#[derive(Debug, Clone)]
+pub struct DropInDirectory {
+    directory: String,
+    includes: Vec<String>,
+    excludes: Vec<String>,
+    ordinal: Option<usize>,
+}
+
+impl DropInDirectory {
+    pub fn new(directory: &str) -> DropInDirectory {
+        DropInDirectory {
+            directory: directory.to_owned(),
+            includes: Vec::new(),
+            excludes: Vec::new(),
+            ordinal: None,
+        }
+    }
+
+    /// Only load files whose name matches the glob pattern. When no include
+    /// patterns are given every supported file is loaded.
+    pub fn include(mut self, pattern: &str) -> Self {
+        self.includes.push(pattern.to_owned());
+        self
+    }
+
+    /// Skip files whose name matches the glob pattern
+    pub fn exclude(mut self, pattern: &str) -> Self {
+        self.excludes.push(pattern.to_owned());
+        self
+    }
+
+    /// Sets the ordinal shared by the files of the directory
+    pub fn ordinal(mut self, ordinal: usize) -> Self {
+        self.ordinal = Some(ordinal);
+        self
+    }
+
+    /// Loads every matching file with the default parse options, failing with the first
+    /// file that cannot be loaded. Ordinals follow the default [`PrecedencePolicy`] and
+    /// the sources are returned with the overriding files first.
+    pub fn load(&self) -> Result<Vec<Box<dyn ConfigSource>>, FileError> {
+        let (sources, errors) = self.load_all(
+            PrecedencePolicy::default(),
+            &YamlParseOptions::default(),
+            &DotEnvParseOptions::default(),
+        );
+        match errors.into_iter().next() {
+            Some(error) => Err(error),
+            None => Ok(sources),
+        }
+    }
+
+    /// Attempts to load every matching file, returning the sources that loaded along
+    /// with an error for each file that did not. Files are parsed like the builder's
+    /// other files of the same kind.
+    pub(crate) fn load_all(
+        &self,
+        precedence: PrecedencePolicy,
+        yaml_options: &YamlParseOptions,
+        dot_env_options: &DotEnvParseOptions,
+    ) -> (Vec<Box<dyn ConfigSource>>, Vec<FileError>) {
+        match self.matching_file_names() {
+            Err(error) => (Vec::new(), vec![error]),
+            Ok(file_names) => {
+                let ordinal = self.ordinal.unwrap_or(precedence.drop_in_ordinal());
+                let mut sources: Vec<Box<dyn ConfigSource>> = Vec::new();
+                let mut errors = Vec::new();
+                // The files share an ordinal and sources sharing an ordinal keep their
+                // order, so the files are returned with the overriding ones first
+                for file_name in file_names.iter().rev() {
+                    match self.load_file(file_name, ordinal, yaml_options, dot_env_options) {
+                        Ok(Some(source)) => sources.push(source),
+                        Ok(None) => {}
+                        Err(error) => errors.push(error),
+                    }
+                }
+                (sources, errors)
+            }
+        }
+    }
+
+    fn matching_file_names(&self) -> Result<Vec<String>, FileError> {
+        let includes = compile_globs(&self.includes);
+        let excludes = compile_globs(&self.excludes);
+
+        let mut file_names = Vec::new();
+        let io_error = |error| FileError::IoError {
+            path: self.directory.clone(),
+            error,
+        };
+        for entry in fs::read_dir(&self.directory).map_err(io_error)? {
+            let entry = entry.map_err(io_error)?;
+            if !entry.file_type().map_err(io_error)?.is_file() {
+                continue;
+            }
+
+            let file_name = entry.file_name().to_string_lossy().into_owned();
+            let is_included =
+                includes.is_empty() || includes.iter().any(|glob| glob.is_match(&file_name));
+            let is_excluded = excludes.iter().any(|glob| glob.is_match(&file_name));
+            if is_included && !is_excluded && FileKind::from_file_name(&file_name).is_some() {
+                file_names.push(file_name);
+            }
+        }
+        file_names.sort();
+        Ok(file_names)
+    }
+
+    fn load_file(
+        &self,
+        file_name: &str,
+        ordinal: usize,
+        yaml_options: &YamlParseOptions,
+        dot_env_options: &DotEnvParseOptions,
+    ) -> Result<Option<Box<dyn ConfigSource>>, FileError> {
+        let file_path = Path::new(&self.directory).join(file_name);
+        let file_path = file_path.to_string_lossy();
+        Ok(match FileKind::from_file_name(file_name) {
+            Some(FileKind::Yaml) => Some(Box::new(
+                YamlConfigSource::from_file_with_options(&file_path, yaml_options)?
+                    .with_default_ordinal(ordinal),
+            )),
+            Some(FileKind::DotEnvironment) => Some(Box::new(
+                DotEnvironmentConfigSource::from_file_with_options(&file_path, dot_env_options)?
+                    .with_default_ordinal(ordinal),
+            )),
+            None => None,
+        })
+    }
+}
+
+enum FileKind {
+    Yaml,
+    DotEnvironment,
+}
+
+impl FileKind {
+    fn from_file_name(file_name: &str) -> Option<FileKind> {
+        if file_name.ends_with(".yaml") || file_name.ends_with(".yml") {
+            Some(FileKind::Yaml)
+        } else if file_name.ends_with(".env") {
+            Some(FileKind::DotEnvironment)
+        } else {
+            None
+        }
+    }
+}
+
+/// Translates glob patterns supporting `*` and `?` into anchored regular expressions
+fn compile_globs(patterns: &[String]) -> Vec<Regex> {
+    patterns
+        .iter()
+        .map(|pattern| {
+            let expression = pattern
+                .split('*')
+                .map(|part| {
+                    part.split('?')
+                        .map(regex::escape)
+                        .collect::<Vec<String>>()
+                        .join(".")
+                })
+                .collect::<Vec<String>>()
+                .join(".*");
+            Regex::new(&format!("^{}$", expression)).unwrap()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn source_names(sources: &[Box<dyn ConfigSource>]) -> Vec<String> {
+        sources
+            .iter()
+            .map(|source| source.get_name().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn loads_supported_files_overriding_files_first() {
+        let sources = DropInDirectory::new("test_configs/config.d")
+            .load()
+            .unwrap();
+
+        assert_eq!(
+            source_names(&sources),
+            vec![
+                "DotEnvironmentConfigSource[test_configs/config.d/90-local.env]",
+                "YamlConfigSource[test_configs/config.d/50-site.yaml]",
+                "YamlConfigSource[test_configs/config.d/10-base.yaml]",
+            ]
+        );
+    }
+
+    #[test]
+    fn files_setting_config_ordinal_keep_it() {
+        let sources = DropInDirectory::new("test_configs/ordinal").load().unwrap();
+        assert_eq!(sources[0].get_ordinal(), 50);
+    }
+
+    #[rstest]
+    #[case(PrecedencePolicy::LowestOrdinalFirst, None, 200)]
+    #[case(PrecedencePolicy::HighestOrdinalFirst, None, 299)]
+    #[case(PrecedencePolicy::LowestOrdinalFirst, Some(0), 0)]
+    #[case(PrecedencePolicy::HighestOrdinalFirst, Some(0), 0)]
+    fn files_share_the_directory_ordinal(
+        #[case] policy: PrecedencePolicy,
+        #[case] ordinal: Option<usize>,
+        #[case] expected_ordinal: usize,
+    ) {
+        let mut drop_in_directory = DropInDirectory::new("test_configs/many.d");
+        if let Some(ordinal) = ordinal {
+            drop_in_directory = drop_in_directory.ordinal(ordinal);
+        }
+        let (sources, errors) = drop_in_directory.load_all(
+            policy,
+            &YamlParseOptions::default(),
+            &DotEnvParseOptions::default(),
+        );
+
+        assert!(errors.is_empty());
+        let ordinals: Vec<usize> = sources.iter().map(|s| s.get_ordinal()).collect();
+        assert_eq!(ordinals, vec![expected_ordinal; 6]);
+    }
+
+    #[test]
+    fn include_and_exclude_patterns() {
+        let sources = DropInDirectory::new("test_configs/config.d")
+            .include("*.yaml")
+            .exclude("5?-*")
+            .load()
+            .unwrap();
+
+        assert_eq!(
+            source_names(&sources),
+            vec!["YamlConfigSource[test_configs/config.d/10-base.yaml]"]
+        );
+    }
+
+    #[test]
+    fn missing_directory_error() {
+        let result = DropInDirectory::new("test_configs/missing.d").load();
+        assert!(matches!(result.err(), Some(FileError::IoError { .. })));
+    }
+
+    #[test]
+    fn glob_translation() {
+        let globs = compile_globs(&["*.y?ml".to_string(), "a+b.env".to_string()]);
+
+        assert!(globs[0].is_match("10-base.yaml"));
+        assert!(!globs[0].is_match("10-base.yml"));
+        assert!(globs[1].is_match("a+b.env"));
+        assert!(!globs[1].is_match("aab.env"));
+    }
+}
@@ -1,23 +1,56 @@
 use super::{
-    config_source::{convert_property_to_environment_name, FileError},
+    config_source::{cached_environment_name, FileError},
     ConfigSource,
 };
 use std::env;
 
+/// Reads properties from environment variables named after them, e.g. `database.user`
+/// from `DATABASE_USER`. Construct it with [`EnvironmentConfigSource::new`] or
+/// [`Default`], the `EnvironmentConfigSource {}` literal is no longer available.
 #[derive(Clone)]
-pub struct EnvironmentConfigSource {}
+pub struct EnvironmentConfigSource {
+    /// Prepended to every environment variable name, e.g. `APP_`
+    prefix: Option<String>,
+    ordinal: usize,
+}
+
+impl EnvironmentConfigSource {
+    pub fn new() -> EnvironmentConfigSource {
+        EnvironmentConfigSource {
+            prefix: None,
+            ordinal: 100,
+        }
+    }
+
+    /// Reads `database.user` from `{prefix}DATABASE_USER` instead of `DATABASE_USER`
+    pub fn with_prefix(mut self, prefix: &str) -> Self {
+        self.prefix = Some(prefix.to_owned());
+        self
+    }
+
+    /// Overrides the default ordinal of this source
+    pub fn with_ordinal(mut self, ordinal: usize) -> Self {
+        self.ordinal = ordinal;
+        self
+    }
+}
 
-impl EnvironmentConfigSource {}
+impl Default for EnvironmentConfigSource {
+    fn default() -> Self {
+        EnvironmentConfigSource::new()
+    }
+}
 
 impl ConfigSource for EnvironmentConfigSource {
     fn get_ordinal(&self) -> usize {
-        100
+        self.ordinal
     }
 
     fn get_value(&self, property_name: &str) -> Option<String> {
-        match env::var(convert_property_to_environment_name(property_name)) {
-            Ok(value) => Some(value),
-            Err(_) => None,
+        let env_name = cached_environment_name(property_name);
+        match &self.prefix {
+            None => env::var(&*env_name).ok(),
+            Some(prefix) => env::var(format!("{}{}", prefix, env_name)).ok(),
         }
     }
 
@@ -29,7 +62,7 @@ impl ConfigSource for EnvironmentConfigSource {
     }
 
     fn from_file(_file_path: &str) -> Result<Self, FileError> {
-        Ok(EnvironmentConfigSource {})
+        Ok(EnvironmentConfigSource::new())
     }
 }
 
@@ -42,11 +75,27 @@ mod tests {
     fn read_environment_variable() {
         env::set_var("TEST_ONE", "blah");
 
-        let config_source = EnvironmentConfigSource {};
+        let config_source = EnvironmentConfigSource::new();
         let value = config_source.get_value("test.one");
         assert_ne!(value, None);
         assert_eq!(value.unwrap(), "blah");
 
         env::remove_var("TEST_ONE");
     }
+
+    #[test]
+    fn read_prefixed_environment_variable() {
+        env::set_var("PREFIX_TEST_PREFIXED_VALUE", "prefixed");
+        env::set_var("PREFIXED_VALUE", "unprefixed");
+
+        let config_source = EnvironmentConfigSource::new().with_prefix("PREFIX_TEST_");
+        assert_eq!(
+            config_source.get_value("prefixed.value"),
+            Some("prefixed".to_string())
+        );
+        assert_eq!(config_source.get_value("missing.value"), None);
+
+        env::remove_var("PREFIX_TEST_PREFIXED_VALUE");
+        env::remove_var("PREFIXED_VALUE");
+    }
 }
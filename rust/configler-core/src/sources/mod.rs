@@ -1,8 +1,14 @@
 pub mod config_source;
 pub mod dot_env;
+pub mod dot_env_document;
+pub mod drop_in;
 pub mod environment;
+pub mod property_path;
 pub mod yaml;
+mod yaml_loader;
 
 pub use self::config_source::ConfigSource;
+pub use self::drop_in::DropInDirectory;
 pub use self::environment::EnvironmentConfigSource;
+pub use self::property_path::PropertyPath;
 pub use self::yaml::YamlConfigSource;
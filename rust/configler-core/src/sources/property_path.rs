@@ -0,0 +1,189 @@
+use core::fmt;
+use std::{error::Error, str::FromStr};
+
+/// A single step in a property path
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// A parsed property name such as `labels."kubernetes.io/name"` or `servers[1].host`.
+///
+/// Segments are separated by `.`, a segment wrapped in double quotes may contain dots,
+/// brackets and escaped quotes (`\"`), and `[n]` selects the n-th element of a sequence.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PropertyPath {
+    segments: Vec<PathSegment>,
+}
+
+impl PropertyPath {
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+}
+
+impl From<Vec<PathSegment>> for PropertyPath {
+    fn from(segments: Vec<PathSegment>) -> Self {
+        PropertyPath { segments }
+    }
+}
+
+impl FromStr for PropertyPath {
+    type Err = PropertyPathError;
+
+    fn from_str(property_name: &str) -> Result<Self, Self::Err> {
+        let mut segments = Vec::new();
+        let mut chars = property_name.chars().peekable();
+
+        loop {
+            // Parse the key of the segment
+            let mut key = String::new();
+            if chars.peek() == Some(&'"') {
+                chars.next();
+                loop {
+                    match chars.next() {
+                        Some('\\') => match chars.next() {
+                            Some(escaped) => key.push(escaped),
+                            None => return Err(PropertyPathError::UnterminatedQuote),
+                        },
+                        Some('"') => break,
+                        Some(char) => key.push(char),
+                        None => return Err(PropertyPathError::UnterminatedQuote),
+                    }
+                }
+                segments.push(PathSegment::Key(key));
+            } else {
+                while let Some(&char) = chars.peek() {
+                    if char == '.' || char == '[' {
+                        break;
+                    }
+                    key.push(char);
+                    chars.next();
+                }
+                if key.is_empty() {
+                    return Err(PropertyPathError::EmptySegment);
+                }
+                segments.push(PathSegment::Key(key));
+            }
+
+            // Parse any indices following the key
+            while chars.peek() == Some(&'[') {
+                chars.next();
+                let mut index = String::new();
+                loop {
+                    match chars.next() {
+                        Some(']') => break,
+                        Some(char) => index.push(char),
+                        None => return Err(PropertyPathError::InvalidIndex),
+                    }
+                }
+                match index.parse::<usize>() {
+                    Ok(index) => segments.push(PathSegment::Index(index)),
+                    Err(_) => return Err(PropertyPathError::InvalidIndex),
+                }
+            }
+
+            match chars.next() {
+                None => return Ok(PropertyPath { segments }),
+                Some('.') => continue,
+                Some(_) => return Err(PropertyPathError::UnexpectedCharacter),
+            }
+        }
+    }
+}
+
+impl fmt::Display for PropertyPath {
+    /// Writes the canonical form of the path, quoting keys only when required
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (position, segment) in self.segments.iter().enumerate() {
+            match segment {
+                PathSegment::Key(key) => {
+                    if position > 0 {
+                        write!(f, ".")?;
+                    }
+                    if key.is_empty() || key.contains(['.', '[', ']', '"', '\\']) {
+                        write!(f, "\"{}\"", key.replace('\\', "\\\\").replace('"', "\\\""))?;
+                    } else {
+                        write!(f, "{}", key)?;
+                    }
+                }
+                PathSegment::Index(index) => write!(f, "[{}]", index)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PropertyPathError {
+    EmptySegment,
+    UnterminatedQuote,
+    InvalidIndex,
+    UnexpectedCharacter,
+}
+
+impl fmt::Display for PropertyPathError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PropertyPathError::EmptySegment => write!(f, "Property name has an empty segment"),
+            PropertyPathError::UnterminatedQuote => {
+                write!(f, "Property name has an unterminated quoted segment")
+            }
+            PropertyPathError::InvalidIndex => {
+                write!(f, "Property name has an invalid sequence index")
+            }
+            PropertyPathError::UnexpectedCharacter => {
+                write!(
+                    f,
+                    "Property name has an unexpected character after a segment"
+                )
+            }
+        }
+    }
+}
+
+impl Error for PropertyPathError {}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::*;
+
+    fn key(key: &str) -> PathSegment {
+        PathSegment::Key(key.to_string())
+    }
+
+    #[rstest]
+    #[case("database", vec![key("database")])]
+    #[case("database.user", vec![key("database"), key("user")])]
+    #[case("labels.\"kubernetes.io/name\"", vec![key("labels"), key("kubernetes.io/name")])]
+    #[case("servers[1].host", vec![key("servers"), PathSegment::Index(1), key("host")])]
+    #[case("matrix[0][2]", vec![key("matrix"), PathSegment::Index(0), PathSegment::Index(2)])]
+    #[case("\"a\\\"b\"", vec![key("a\"b")])]
+    #[case("some-app-config.is_feature_on", vec![key("some-app-config"), key("is_feature_on")])]
+    fn parse_property_paths(#[case] property_name: &str, #[case] expected: Vec<PathSegment>) {
+        let path = PropertyPath::from_str(property_name).unwrap();
+        assert_eq!(path.segments(), expected.as_slice());
+        assert_eq!(path.to_string(), property_name);
+    }
+
+    #[rstest]
+    #[case("", PropertyPathError::EmptySegment)]
+    #[case("database.", PropertyPathError::EmptySegment)]
+    #[case("a..b", PropertyPathError::EmptySegment)]
+    #[case("labels.\"unterminated", PropertyPathError::UnterminatedQuote)]
+    #[case("servers[one]", PropertyPathError::InvalidIndex)]
+    #[case("servers[1", PropertyPathError::InvalidIndex)]
+    #[case("\"quoted\"suffix", PropertyPathError::UnexpectedCharacter)]
+    fn invalid_property_paths(#[case] property_name: &str, #[case] expected: PropertyPathError) {
+        assert_eq!(PropertyPath::from_str(property_name).err(), Some(expected));
+    }
+
+    #[test]
+    fn canonical_form_quotes_only_when_needed() {
+        let path = PropertyPath::from_str("\"plain\".\"with.dot\"").unwrap();
+        assert_eq!(path.to_string(), "plain.\"with.dot\"");
+    }
+}
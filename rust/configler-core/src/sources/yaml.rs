@@ -1,90 +1,876 @@
 use core::fmt;
 use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    error::Error,
     fs::{self},
+    io, mem,
+    path::{Path, PathBuf},
     str::FromStr,
+    sync::Arc,
 };
 
-use yaml_rust2::{Yaml, YamlLoader};
+use yaml_rust2::{
+    parser::{MarkedEventReceiver, Parser, Tag},
+    scanner::Marker,
+    yaml::Hash,
+    Event, Yaml,
+};
+
+#[cfg(feature = "diagnostics")]
+use crate::diagnostics::Diagnostic;
 
-use super::{config_source::FileError, ConfigSource};
+use super::{
+    config_source::{FileError, CONFIG_ORDINAL_PROPERTY},
+    property_path::{PathSegment, PropertyPath},
+    yaml_loader::{load_documents, INCLUDE_TAG},
+    ConfigSource,
+};
 
+/// A YAML file flattened at load time into an index of scalar values keyed by their
+/// canonical property name, e.g. `servers[0].host`. Clones share the index.
 #[derive(Clone)]
 pub struct YamlConfigSource {
-    yaml_doc: Yaml,
+    index: Arc<HashMap<String, String>>,
+    ordinal: usize,
+    /// Whether the ordinal came from the `config_ordinal` property
+    has_config_ordinal: bool,
+    name: String,
 }
 
-impl ConfigSource for YamlConfigSource {
-    fn get_ordinal(&self) -> usize {
-        265
+/// Options controlling how YAML content is parsed
+#[derive(Debug, Clone, Default)]
+pub struct YamlParseOptions {
+    /// The active profile used to select profile specific documents
+    pub profile: Option<String>,
+    /// Rejects documents whose root is not a mapping, duplicate keys and tags other
+    /// than the YAML core schema tags instead of accepting them leniently
+    pub strict: bool,
+}
+
+/// Key marking a document in a multi-document file as specific to one or more
+/// comma separated profiles, written either nested or as a single dotted key
+const PROFILE_ACTIVATION_KEYS: [&str; 2] = ["configler.profile", "\"configler.profile\""];
+
+/// Top-level key listing files merged underneath the document that declares them
+const INCLUDES_KEY: &str = "includes";
+
+impl YamlConfigSource {
+    /// Overrides the default ordinal of this source, including one set with
+    /// `config_ordinal`
+    pub fn with_ordinal(mut self, ordinal: usize) -> Self {
+        self.ordinal = ordinal;
+        self
     }
 
-    fn get_value(&self, property_name: &str) -> Option<String> {
-        let mut current_node = &self.yaml_doc;
-        for key in property_name.split('.') {
-            if current_node[key].is_null() {
-                return None;
-            } else {
-                current_node = &current_node[key];
+    /// Sets the ordinal unless the content sets its own with `config_ordinal`
+    pub(crate) fn with_default_ordinal(self, ordinal: usize) -> Self {
+        if self.has_config_ordinal {
+            self
+        } else {
+            self.with_ordinal(ordinal)
+        }
+    }
+
+    pub fn from_file_with_options(
+        file_path: &str,
+        options: &YamlParseOptions,
+    ) -> Result<Self, FileError> {
+        YamlConfigSource::load_file(file_path, options, true)
+    }
+
+    /// Loads a file applying every document in order, where `configler.profile` is an
+    /// ordinary property rather than a profile activation as in bootstrap files
+    pub(crate) fn from_file_without_profiles(file_path: &str) -> Result<Self, FileError> {
+        YamlConfigSource::load_file(file_path, &YamlParseOptions::default(), false)
+    }
+
+    fn load_file(
+        file_path: &str,
+        options: &YamlParseOptions,
+        profile_activation: bool,
+    ) -> Result<Self, FileError> {
+        match fs::read_to_string(file_path) {
+            Err(error) => Err(FileError::IoError {
+                path: file_path.to_owned(),
+                error,
+            }),
+            Ok(file_content) => {
+                // The including file starts the chain so that cycles back to it are detected
+                let mut include_chain = fs::canonicalize(file_path)
+                    .map(|canonical_path| {
+                        vec![IncludedFile {
+                            canonical_path,
+                            display_path: file_path.to_owned(),
+                        }]
+                    })
+                    .unwrap_or_default();
+                let base_directory = Path::new(file_path).parent().unwrap_or(Path::new(""));
+
+                load_yaml(
+                    &file_content,
+                    base_directory,
+                    options,
+                    profile_activation,
+                    &mut include_chain,
+                )
+                .and_then(|yaml_doc| {
+                    YamlConfigSource::new(
+                        yaml_doc,
+                        &format!("{}[{}]", YamlConfigSource::type_name(), file_path),
+                    )
+                })
+                .map_err(|error| FileError::YamlParseError {
+                    path: file_path.to_owned(),
+                    error,
+                })
             }
         }
+    }
 
-        current_node.as_str().map(|str_ref| str_ref.to_string())
+    /// Parses YAML content which may hold multiple documents. Documents without a
+    /// `configler.profile` key form the base configuration and documents tagged with
+    /// the active profile are applied on top of it. Within each group later documents
+    /// override earlier ones. Included files are resolved from the current directory.
+    pub fn from_str_with_options(
+        yaml_str: &str,
+        options: &YamlParseOptions,
+    ) -> Result<Self, YamlParseError> {
+        let yaml_doc = load_yaml(yaml_str, Path::new(""), options, true, &mut Vec::new())?;
+        YamlConfigSource::new(yaml_doc, &YamlConfigSource::type_name())
     }
 
-    fn get_name(&self) -> &str {
+    fn new(yaml_doc: Yaml, name: &str) -> Result<Self, YamlParseError> {
+        let mut index = HashMap::new();
+        flatten_into(&yaml_doc, &mut Vec::new(), &mut index);
+        let config_ordinal = match index.get(CONFIG_ORDINAL_PROPERTY) {
+            None => None,
+            Some(value) => Some(
+                value
+                    .trim()
+                    .parse()
+                    .map_err(|_| YamlParseError::InvalidOrdinal(value.clone()))?,
+            ),
+        };
+        Ok(YamlConfigSource {
+            index: Arc::new(index),
+            ordinal: config_ordinal.unwrap_or(265),
+            has_config_ordinal: config_ordinal.is_some(),
+            name: name.to_owned(),
+        })
+    }
+
+    /// Canonical names of every property holding a value, sorted by name
+    pub fn property_names(&self) -> Vec<&str> {
+        let mut property_names: Vec<&str> = self.index.keys().map(String::as_str).collect();
+        property_names.sort_unstable();
+        property_names
+    }
+
+    /// Properties nested under `prefix` with their values, sorted by name. The prefix
+    /// must end on a segment boundary, so `database` matches `database.user` and
+    /// `database[0]` but not `databases.user`.
+    pub fn properties_with_prefix(&self, prefix: &str) -> Vec<(&str, &str)> {
+        let Ok(prefix) = PropertyPath::from_str(prefix).map(|path| path.to_string()) else {
+            return Vec::new();
+        };
+        let mut properties: Vec<(&str, &str)> = self
+            .index
+            .iter()
+            .filter(|(property_name, _)| {
+                property_name
+                    .strip_prefix(&prefix)
+                    .is_some_and(|rest| rest.starts_with(['.', '[']))
+            })
+            .map(|(property_name, value)| (property_name.as_str(), value.as_str()))
+            .collect();
+        properties.sort_unstable();
+        properties
+    }
+
+    fn lookup(&self, property_name: &str) -> Option<&str> {
+        // Most lookups already use the canonical form so try it before parsing
+        if let Some(value) = self.index.get(property_name) {
+            return Some(value);
+        }
+        // Without quotes, escapes or closing brackets a valid name is already canonical
+        if !property_name.contains(['"', '\\', ']']) {
+            return None;
+        }
+        let canonical_name = PropertyPath::from_str(property_name).ok()?.to_string();
+        self.index.get(&canonical_name).map(String::as_str)
+    }
+
+    fn type_name() -> String {
         std::any::type_name::<YamlConfigSource>()
             .split("::")
             .last()
             .unwrap()
+            .to_string()
     }
+}
 
-    fn from_file(file_path: &str) -> Result<Self, FileError> {
-        match fs::read_to_string(file_path) {
-            Err(error) => Err(FileError::IoError(error)),
-            Ok(file_content) => match YamlConfigSource::from_str(&file_content) {
-                Err(parse_error) => Err(FileError::YamlParseError(parse_error)),
-                Ok(config_source) => Ok(config_source),
-            },
+/// A file in the chain of includes currently being loaded
+struct IncludedFile {
+    canonical_path: PathBuf,
+    display_path: String,
+}
+
+/// Loads and merges the documents of `yaml_str`, resolving included files relative
+/// to `base_directory`
+/// Documents are selected by their `configler.profile` key only when
+/// `profile_activation` is set, otherwise every document is applied
+fn load_yaml(
+    yaml_str: &str,
+    base_directory: &Path,
+    options: &YamlParseOptions,
+    profile_activation: bool,
+    include_chain: &mut Vec<IncludedFile>,
+) -> Result<Yaml, YamlParseError> {
+    if options.strict {
+        let violations = validate_strict(yaml_str);
+        if !violations.is_empty() {
+            return Err(YamlParseError::StrictViolations(violations));
+        }
+    }
+
+    let yaml_docs = load_documents(yaml_str, &mut |include_path| {
+        load_include(
+            include_path,
+            base_directory,
+            options,
+            profile_activation,
+            include_chain,
+        )
+    })?;
+
+    let mut base_docs = Vec::new();
+    let mut profile_docs = Vec::new();
+    // Empty documents are loaded as null and contribute nothing
+    let yaml_docs = yaml_docs
+        .into_iter()
+        .filter(|doc| !(doc.is_null() || doc.is_badvalue()))
+        .map(resolve_merge_keys);
+    for yaml_doc in yaml_docs {
+        let activation = PROFILE_ACTIVATION_KEYS
+            .iter()
+            .filter(|_| profile_activation)
+            .find_map(|activation_key| find_node(&yaml_doc, activation_key));
+        match activation {
+            None => base_docs.push(yaml_doc),
+            Some(profiles) if is_profile_active(profiles, options.profile.as_deref()) => {
+                profile_docs.push(yaml_doc)
+            }
+            Some(_) => {}
         }
     }
+
+    let mut selected_docs = Vec::new();
+    for yaml_doc in base_docs.into_iter().chain(profile_docs) {
+        selected_docs.push(apply_includes_list(
+            yaml_doc,
+            base_directory,
+            options,
+            profile_activation,
+            include_chain,
+        )?);
+    }
+    let mut selected_docs = selected_docs.into_iter();
+    let first_doc = selected_docs
+        .next()
+        .unwrap_or(Yaml::Hash(Default::default()));
+    Ok(selected_docs.fold(first_doc, merge_documents))
 }
 
-impl FromStr for YamlConfigSource {
-    type Err = YamlParseError;
+/// Loads the file at `include_path`, relative to `base_directory`, with the same options
+/// and profile activation as the including file
+fn load_include(
+    include_path: &str,
+    base_directory: &Path,
+    options: &YamlParseOptions,
+    profile_activation: bool,
+    include_chain: &mut Vec<IncludedFile>,
+) -> Result<Yaml, YamlParseError> {
+    let file_path = base_directory.join(include_path);
+    let display_path = file_path.to_string_lossy().into_owned();
+    let include_error = |include_chain: &[IncludedFile], kind: IncludeErrorKind| {
+        YamlParseError::IncludeError(IncludeError {
+            include_chain: include_chain
+                .iter()
+                .map(|included_file| included_file.display_path.clone())
+                .chain([display_path.clone()])
+                .collect(),
+            kind,
+        })
+    };
 
-    fn from_str(yaml_str: &str) -> Result<Self, Self::Err> {
-        let loader_result = YamlLoader::load_from_str(yaml_str);
-        match loader_result {
-            Ok(yaml_docs) => {
-                if yaml_docs.len() > 1 {
-                    // Yaml Multi-docs allow there to be conflicting keys
-                    // in the Yaml file which would make resolving key names
-                    // ambiguous. To avoid that we will not support multi-doc
-                    return Err(YamlParseError::UnsupportedMultiDoc);
+    let canonical_path = fs::canonicalize(&file_path)
+        .map_err(|error| include_error(include_chain, IncludeErrorKind::Io(error.kind())))?;
+    if include_chain
+        .iter()
+        .any(|included_file| included_file.canonical_path == canonical_path)
+    {
+        return Err(include_error(include_chain, IncludeErrorKind::Cycle));
+    }
+    let file_content = fs::read_to_string(&file_path)
+        .map_err(|error| include_error(include_chain, IncludeErrorKind::Io(error.kind())))?;
+
+    include_chain.push(IncludedFile {
+        canonical_path,
+        display_path: display_path.clone(),
+    });
+    let base_directory = file_path.parent().unwrap_or(Path::new(""));
+    let result = load_yaml(
+        &file_content,
+        base_directory,
+        options,
+        profile_activation,
+        include_chain,
+    );
+    include_chain.pop();
+
+    result.map_err(|error| match error {
+        // Errors from nested includes already carry the full chain
+        YamlParseError::IncludeError(error) => YamlParseError::IncludeError(error),
+        error => include_error(include_chain, IncludeErrorKind::Parse(Box::new(error))),
+    })
+}
+
+/// Merges the files listed under a top-level `includes` key underneath `yaml_doc`,
+/// in order, so that the document itself overrides what it includes
+fn apply_includes_list(
+    yaml_doc: Yaml,
+    base_directory: &Path,
+    options: &YamlParseOptions,
+    profile_activation: bool,
+    include_chain: &mut Vec<IncludedFile>,
+) -> Result<Yaml, YamlParseError> {
+    let Yaml::Hash(mut hash) = yaml_doc else {
+        return Ok(yaml_doc);
+    };
+    let Some(includes) = hash.remove(&Yaml::String(INCLUDES_KEY.to_owned())) else {
+        return Ok(Yaml::Hash(hash));
+    };
+
+    let include_paths: Option<Vec<&str>> = match &includes {
+        Yaml::Array(items) => items.iter().map(|item| item.as_str()).collect(),
+        _ => None,
+    };
+    let Some(include_paths) = include_paths else {
+        return Err(YamlParseError::IncludeError(IncludeError {
+            include_chain: include_chain
+                .iter()
+                .map(|included_file| included_file.display_path.clone())
+                .collect(),
+            kind: IncludeErrorKind::InvalidIncludesList,
+        }));
+    };
+
+    let mut included_doc = Yaml::Hash(Default::default());
+    for include_path in include_paths {
+        let included = load_include(
+            include_path,
+            base_directory,
+            options,
+            profile_activation,
+            include_chain,
+        )?;
+        included_doc = merge_documents(included_doc, included);
+    }
+    Ok(merge_documents(included_doc, Yaml::Hash(hash)))
+}
+
+/// Adds every scalar below `node` to `index` under its canonical property name. Only
+/// string keys are addressable by a property name, so other keys are skipped.
+fn flatten_into(node: &Yaml, path: &mut Vec<PathSegment>, index: &mut HashMap<String, String>) {
+    match node {
+        Yaml::Hash(hash) => {
+            for (key, value) in hash {
+                if let Some(key) = key.as_str() {
+                    path.push(PathSegment::Key(key.to_owned()));
+                    flatten_into(value, path, index);
+                    path.pop();
                 }
-                Ok(YamlConfigSource {
-                    yaml_doc: yaml_docs[0].clone(),
-                })
             }
-            Err(scan_error) => Err(YamlParseError::ScanError(scan_error)),
         }
+        Yaml::Array(items) => {
+            for (position, item) in items.iter().enumerate() {
+                path.push(PathSegment::Index(position));
+                flatten_into(item, path, index);
+                path.pop();
+            }
+        }
+        node => {
+            if let (false, Some(value)) = (path.is_empty(), scalar_to_string(node)) {
+                index.insert(PropertyPath::from(path.clone()).to_string(), value);
+            }
+        }
+    }
+}
+
+/// Walks `property_name` from `root`, returning `None` when any segment is missing
+fn find_node<'a>(root: &'a Yaml, property_name: &str) -> Option<&'a Yaml> {
+    let property_path = PropertyPath::from_str(property_name).ok()?;
+
+    let mut current_node = root;
+    for segment in property_path.segments() {
+        let next_node = match segment {
+            PathSegment::Key(key) => &current_node[key.as_str()],
+            PathSegment::Index(index) if current_node.is_array() => &current_node[*index],
+            PathSegment::Index(_) => return None,
+        };
+        // Indexing a missing key returns BadValue, which is distinct from an explicit null
+        if next_node.is_badvalue() {
+            return None;
+        }
+        current_node = next_node;
+    }
+    Some(current_node)
+}
+
+fn is_profile_active(profiles: &Yaml, active_profile: Option<&str>) -> bool {
+    let Some(active_profile) = active_profile else {
+        return false;
+    };
+    let document_profiles: Vec<String> = match profiles {
+        Yaml::Array(profiles) => profiles.iter().filter_map(scalar_to_string).collect(),
+        profiles => scalar_to_string(profiles)
+            .map(|profiles| profiles.split(',').map(|p| p.trim().to_string()).collect())
+            .unwrap_or_default(),
+    };
+    document_profiles
+        .iter()
+        .any(|profile| profile == active_profile)
+}
+
+/// Key whose value is a mapping, or sequence of mappings, merged into the enclosing mapping
+const MERGE_KEY: &str = "<<";
+
+/// Applies `<<` merge keys throughout `node`. Aliases are already expanded by the
+/// loader, so merged mappings are resolved first to support nested merges. Keys
+/// written in the mapping override merged keys, and earlier mappings in a merged
+/// sequence override later ones.
+fn resolve_merge_keys(node: Yaml) -> Yaml {
+    match node {
+        Yaml::Hash(hash) => {
+            let mut merged_hashes = Vec::new();
+            let mut explicit_hash = Hash::new();
+            for (key, value) in hash {
+                let value = resolve_merge_keys(value);
+                if key.as_str() != Some(MERGE_KEY) {
+                    explicit_hash.insert(key, value);
+                    continue;
+                }
+                match value {
+                    Yaml::Hash(merged_hash) => merged_hashes.push(merged_hash),
+                    Yaml::Array(items) if items.iter().all(|item| item.as_hash().is_some()) => {
+                        merged_hashes.extend(items.into_iter().filter_map(|item| match item {
+                            Yaml::Hash(merged_hash) => Some(merged_hash),
+                            _ => None,
+                        }))
+                    }
+                    value => {
+                        explicit_hash.insert(key, value);
+                    }
+                }
+            }
+
+            let mut resolved_hash = Hash::new();
+            for (key, value) in merged_hashes.into_iter().flatten() {
+                if !resolved_hash.contains_key(&key) {
+                    resolved_hash.insert(key, value);
+                }
+            }
+            for (key, value) in explicit_hash {
+                resolved_hash.insert(key, value);
+            }
+            Yaml::Hash(resolved_hash)
+        }
+        Yaml::Array(items) => Yaml::Array(items.into_iter().map(resolve_merge_keys).collect()),
+        node => node,
+    }
+}
+
+/// Deep merges `overrides` into `base`. Mappings are merged key by key while any
+/// other value in `overrides` replaces the value in `base`.
+fn merge_documents(base: Yaml, overrides: Yaml) -> Yaml {
+    match (base, overrides) {
+        (Yaml::Hash(mut base_hash), Yaml::Hash(override_hash)) => {
+            for (key, override_value) in override_hash {
+                match base_hash.get_mut(&key) {
+                    Some(base_value) => {
+                        let previous_value = mem::replace(base_value, Yaml::Null);
+                        *base_value = merge_documents(previous_value, override_value);
+                    }
+                    None => {
+                        base_hash.insert(key, override_value);
+                    }
+                }
+            }
+            Yaml::Hash(base_hash)
+        }
+        (_, overrides) => overrides,
+    }
+}
+
+/// Tags of the YAML core schema accepted in strict mode
+const CORE_SCHEMA_TAGS: [&str; 7] = ["str", "int", "float", "bool", "null", "map", "seq"];
+
+/// Walks the parser events of `yaml_str` collecting every strict mode violation.
+/// Parsing stops at the first syntax error, which is reported as the last violation.
+fn validate_strict(yaml_str: &str) -> Vec<YamlViolation> {
+    let mut validator = StrictValidator::default();
+    if let Err(error) = Parser::new_from_str(yaml_str).load(&mut validator, true) {
+        validator.violations.push(YamlViolation {
+            line: error.marker().line(),
+            column: error.marker().col() + 1,
+            kind: YamlViolationKind::InvalidSyntax(error.info().to_owned()),
+        });
+    }
+    validator.violations
+}
+
+#[derive(Default)]
+struct StrictValidator {
+    /// Open collections, innermost last
+    frames: Vec<Frame>,
+    at_document_root: bool,
+    violations: Vec<YamlViolation>,
+}
+
+enum Frame {
+    Mapping {
+        keys: HashSet<String>,
+        expecting_key: bool,
+    },
+    Sequence,
+}
+
+impl StrictValidator {
+    fn report(&mut self, mark: Marker, kind: YamlViolationKind) {
+        self.violations.push(YamlViolation {
+            line: mark.line(),
+            column: mark.col() + 1,
+            kind,
+        });
+    }
+
+    fn check_tag(&mut self, tag: &Option<Tag>, mark: Marker) {
+        if let Some(tag) = tag {
+            let is_core_tag = (tag.handle == "tag:yaml.org,2002:" || tag.handle == "!!")
+                && CORE_SCHEMA_TAGS.contains(&tag.suffix.as_str());
+            let is_include_tag = (tag.handle.as_str(), tag.suffix.as_str()) == INCLUDE_TAG;
+            if !is_core_tag && !is_include_tag {
+                let tag = format!("{}{}", tag.handle, tag.suffix);
+                self.report(mark, YamlViolationKind::UnsupportedTag(tag));
+            }
+        }
+    }
+
+    /// Checks the root and mapping key rules against the first event of a node
+    fn start_node(&mut self, event: &Event, mark: Marker) {
+        if mem::take(&mut self.at_document_root) {
+            let is_empty_document = matches!(
+                event,
+                Event::Scalar(value, _, _, None) if value.is_empty() || value == "~" || value == "null"
+            );
+            if !matches!(event, Event::MappingStart(..)) && !is_empty_document {
+                self.report(mark, YamlViolationKind::NonMappingRoot);
+            }
+        }
+
+        if let Some(Frame::Mapping {
+            keys,
+            expecting_key: true,
+        }) = self.frames.last_mut()
+        {
+            if let Event::Scalar(key, ..) = event {
+                if !keys.insert(key.to_owned()) {
+                    let key = key.to_owned();
+                    self.report(mark, YamlViolationKind::DuplicateKey(key));
+                }
+            }
+        }
+    }
+
+    /// Alternates the enclosing mapping between expecting a key and a value
+    fn end_node(&mut self) {
+        if let Some(Frame::Mapping { expecting_key, .. }) = self.frames.last_mut() {
+            *expecting_key = !*expecting_key;
+        }
+    }
+}
+
+impl MarkedEventReceiver for StrictValidator {
+    fn on_event(&mut self, event: Event, mark: Marker) {
+        match &event {
+            Event::DocumentStart => self.at_document_root = true,
+            Event::Alias(_) => {
+                self.start_node(&event, mark);
+                self.end_node();
+            }
+            Event::Scalar(_, _, _, tag) => {
+                self.start_node(&event, mark);
+                self.check_tag(tag, mark);
+                self.end_node();
+            }
+            Event::SequenceStart(_, tag) => {
+                self.start_node(&event, mark);
+                self.check_tag(tag, mark);
+                self.frames.push(Frame::Sequence);
+            }
+            Event::MappingStart(_, tag) => {
+                self.start_node(&event, mark);
+                self.check_tag(tag, mark);
+                self.frames.push(Frame::Mapping {
+                    keys: HashSet::new(),
+                    expecting_key: true,
+                });
+            }
+            Event::SequenceEnd | Event::MappingEnd => {
+                self.frames.pop();
+                self.end_node();
+            }
+            _ => {}
+        }
+    }
+}
+
+impl ConfigSource for YamlConfigSource {
+    fn get_ordinal(&self) -> usize {
+        self.ordinal
+    }
+
+    fn get_value(&self, property_name: &str) -> Option<String> {
+        self.lookup(property_name).map(str::to_owned)
+    }
+
+    fn get_value_ref(&self, property_name: &str) -> Option<Cow<'_, str>> {
+        self.lookup(property_name).map(Cow::Borrowed)
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn from_file(file_path: &str) -> Result<Self, FileError> {
+        YamlConfigSource::from_file_with_options(file_path, &Default::default())
+    }
+}
+
+/// Converts a scalar node into its canonical string form. Explicit nulls are present
+/// but empty, mirroring an empty dotenv value, while mappings and sequences have no
+/// string form.
+fn scalar_to_string(node: &Yaml) -> Option<String> {
+    match node {
+        Yaml::String(value) => Some(value.to_owned()),
+        Yaml::Boolean(value) => Some(value.to_string()),
+        Yaml::Integer(value) => Some(value.to_string()),
+        Yaml::Real(_) => node.as_f64().map(|value| format!("{:?}", value)),
+        Yaml::Null => Some(String::new()),
+        _ => None,
+    }
+}
+
+impl FromStr for YamlConfigSource {
+    type Err = YamlParseError;
+
+    fn from_str(yaml_str: &str) -> Result<Self, Self::Err> {
+        YamlConfigSource::from_str_with_options(yaml_str, &Default::default())
     }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum YamlParseError {
     ScanError(yaml_rust2::ScanError),
-    UnsupportedMultiDoc,
+    /// Every problem found while parsing in strict mode
+    StrictViolations(Vec<YamlViolation>),
+    IncludeError(IncludeError),
+    /// The `config_ordinal` property is not a non-negative integer
+    InvalidOrdinal(String),
 }
 
 impl fmt::Display for YamlParseError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             YamlParseError::ScanError(error) => write!(f, "{}", error),
-            YamlParseError::UnsupportedMultiDoc => {
-                write!(f, "Yaml Multi-doc features are not supported")
+            YamlParseError::StrictViolations(violations) => {
+                writeln!(f, "YAML content is not valid in strict mode:")?;
+                for violation in violations {
+                    writeln!(f, "  {}", violation)?;
+                }
+                Ok(())
+            }
+            YamlParseError::IncludeError(error) => write!(f, "{}", error),
+            YamlParseError::InvalidOrdinal(value) => write!(
+                f,
+                "'{}' must be a non-negative integer, found '{}'",
+                CONFIG_ORDINAL_PROPERTY, value
+            ),
+        }
+    }
+}
+
+impl Error for YamlParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            YamlParseError::ScanError(error) => Some(error),
+            YamlParseError::StrictViolations(_) | YamlParseError::InvalidOrdinal(_) => None,
+            YamlParseError::IncludeError(error) => Some(error),
+        }
+    }
+}
+
+/// A problem with an included file along with the chain of files that led to it,
+/// starting with the outermost including file and ending with the failing file
+#[derive(Debug, Clone, PartialEq)]
+pub struct IncludeError {
+    pub include_chain: Vec<String>,
+    pub kind: IncludeErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum IncludeErrorKind {
+    Cycle,
+    Io(io::ErrorKind),
+    Parse(Box<YamlParseError>),
+    /// The `includes` key does not hold a sequence of file paths
+    InvalidIncludesList,
+}
+
+impl fmt::Display for IncludeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.kind {
+            IncludeErrorKind::Cycle => write!(f, "Include cycle detected")?,
+            IncludeErrorKind::Io(kind) => write!(f, "Failed to read included file: {}", kind)?,
+            IncludeErrorKind::Parse(error) => {
+                write!(f, "Failed to parse included file: {}", error)?
+            }
+            IncludeErrorKind::InvalidIncludesList => {
+                write!(f, "'{}' must be a sequence of file paths", INCLUDES_KEY)?
             }
         }
+        write!(f, " (include chain: {})", self.include_chain.join(" -> "))
+    }
+}
+
+impl Error for IncludeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match &self.kind {
+            IncludeErrorKind::Parse(error) => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// A strict mode problem at a 1-based line and column
+#[derive(Debug, Clone, PartialEq)]
+pub struct YamlViolation {
+    pub line: usize,
+    pub column: usize,
+    pub kind: YamlViolationKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum YamlViolationKind {
+    NonMappingRoot,
+    DuplicateKey(String),
+    UnsupportedTag(String),
+    InvalidSyntax(String),
+}
+
+impl fmt::Display for YamlViolation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {} column {}: {}",
+            self.line, self.column, self.kind
+        )
+    }
+}
+
+impl fmt::Display for YamlViolationKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            YamlViolationKind::NonMappingRoot => write!(f, "Document root is not a mapping"),
+            YamlViolationKind::DuplicateKey(key) => write!(f, "Duplicate key '{}'", key),
+            YamlViolationKind::UnsupportedTag(tag) => write!(f, "Unsupported tag '{}'", tag),
+            YamlViolationKind::InvalidSyntax(info) => write!(f, "{}", info),
+        }
+    }
+}
+
+#[cfg(feature = "diagnostics")]
+impl YamlViolationKind {
+    fn help(&self) -> &'static str {
+        match self {
+            YamlViolationKind::NonMappingRoot => {
+                "configuration documents must start with `key: value` pairs"
+            }
+            YamlViolationKind::DuplicateKey(_) => "remove one of the duplicate keys",
+            YamlViolationKind::UnsupportedTag(_) => {
+                "strict mode only accepts core schema tags such as !!str, and !include"
+            }
+            YamlViolationKind::InvalidSyntax(_) => SYNTAX_HELP,
+        }
+    }
+}
+
+#[cfg(feature = "diagnostics")]
+const ORDINAL_HELP: &str = "set config_ordinal to a whole number such as 250";
+
+#[cfg(feature = "diagnostics")]
+const SYNTAX_HELP: &str = "check the indentation and quoting around this position";
+
+#[cfg(feature = "diagnostics")]
+impl YamlParseError {
+    /// Diagnostics for every problem found in `file_name`. Problems in included
+    /// files are reported against the included file.
+    pub fn diagnostics(&self, file_name: &str) -> Vec<Diagnostic> {
+        match self {
+            YamlParseError::ScanError(error) => vec![Diagnostic {
+                file_name: file_name.to_owned(),
+                location: Some((error.marker().line(), error.marker().col() + 1)),
+                message: error.info().to_owned(),
+                help: Some(SYNTAX_HELP),
+            }],
+            YamlParseError::StrictViolations(violations) => violations
+                .iter()
+                .map(|violation| Diagnostic {
+                    file_name: file_name.to_owned(),
+                    location: Some((violation.line, violation.column)),
+                    message: violation.kind.to_string(),
+                    help: Some(violation.kind.help()),
+                })
+                .collect(),
+            YamlParseError::IncludeError(error) => {
+                let failing_file = error.include_chain.last().map_or(file_name, String::as_str);
+                let help = match &error.kind {
+                    IncludeErrorKind::Parse(parse_error) => {
+                        return parse_error.diagnostics(failing_file)
+                    }
+                    IncludeErrorKind::Cycle => "remove the include that leads back to this file",
+                    IncludeErrorKind::Io(_) => {
+                        "included paths are resolved from the directory of the including file"
+                    }
+                    IncludeErrorKind::InvalidIncludesList => {
+                        "list the files to include, e.g. `includes: [database.yaml]`"
+                    }
+                };
+                vec![Diagnostic {
+                    file_name: failing_file.to_owned(),
+                    location: None,
+                    message: error.to_string(),
+                    help: Some(help),
+                }]
+            }
+            YamlParseError::InvalidOrdinal(_) => vec![Diagnostic {
+                file_name: file_name.to_owned(),
+                location: None,
+                message: self.to_string(),
+                help: Some(ORDINAL_HELP),
+            }],
+        }
     }
 }
 
@@ -103,9 +889,14 @@ mod tests {
         assert!(config_result.is_ok());
 
         let config_source = config_result.unwrap();
-        let db_config = config_source.yaml_doc["database"].clone();
-        assert_eq!(db_config["username"].as_str(), Some("foo"));
-        assert_eq!(db_config["password"].as_str(), Some("bar"));
+        assert_eq!(
+            config_source.get_value("database.username"),
+            Some("foo".to_string())
+        );
+        assert_eq!(
+            config_source.get_value("database.password"),
+            Some("bar".to_string())
+        );
     }
 
     #[test]
@@ -123,6 +914,251 @@ mod tests {
         assert!(config_result.is_ok());
     }
 
+    fn parse_strict(yaml_str: &str) -> Result<YamlConfigSource, YamlParseError> {
+        YamlConfigSource::from_str_with_options(
+            yaml_str,
+            &YamlParseOptions {
+                strict: true,
+                ..Default::default()
+            },
+        )
+    }
+
+    fn strict_violations(yaml_str: &str) -> Vec<(usize, usize, YamlViolationKind)> {
+        match parse_strict(yaml_str) {
+            Err(YamlParseError::StrictViolations(violations)) => violations
+                .into_iter()
+                .map(|violation| (violation.line, violation.column, violation.kind))
+                .collect(),
+            other => panic!("expected strict violations, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn strict_mode_rejects_non_mapping_roots() {
+        assert_eq!(
+            strict_violations("some value\nwith other text\n"),
+            vec![(1, 1, YamlViolationKind::NonMappingRoot)]
+        );
+        assert_eq!(
+            strict_violations("a: 1\n---\n- one\n- two\n"),
+            vec![(3, 1, YamlViolationKind::NonMappingRoot)]
+        );
+    }
+
+    #[test]
+    fn strict_mode_reports_every_problem() {
+        let yaml_str = "\
+database:
+  user: foo
+  user: bar
+  port: !custom 5432
+  nested:
+    key: 1
+    key: 2
+  ok: !!str 10
+";
+        assert_eq!(
+            strict_violations(yaml_str),
+            vec![
+                (3, 3, YamlViolationKind::DuplicateKey("user".to_string())),
+                (
+                    4,
+                    17,
+                    YamlViolationKind::UnsupportedTag("!custom".to_string())
+                ),
+                (7, 5, YamlViolationKind::DuplicateKey("key".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn strict_mode_reports_syntax_errors() {
+        let violations = strict_violations("database:\n  user: foo\n  missing colon\n");
+        assert_eq!(violations.len(), 1);
+        assert!(matches!(
+            violations[0],
+            (4, _, YamlViolationKind::InvalidSyntax(_))
+        ));
+    }
+
+    #[test]
+    fn strict_mode_accepts_valid_documents() {
+        let config_source =
+            parse_strict("database:\n  user: foo\n---\n---\nlist: [1, 1]\n").unwrap();
+        assert_eq!(
+            config_source.get_value("database.user"),
+            Some("foo".to_string())
+        );
+        assert!(parse_strict("").is_ok());
+    }
+
+    const MERGE_YAML: &str = "
+base: &base
+  timeout: 30
+  retries: 3
+defaults: &defaults
+  <<: *base
+  host: localhost
+  retries: 5
+  pool: &pool
+    size: 5
+logging: &logging
+  level: info
+  timeout: 10
+environments:
+  dev:
+    <<: *defaults
+  prod:
+    <<: [*logging, *defaults]
+    host: db.prod.internal
+    pool:
+      <<: *pool
+      max: 50
+replicas:
+  - *defaults
+  - <<: *defaults
+    host: replica.internal
+ports: &ports [80, 443]
+exposed: *ports
+";
+
+    #[test]
+    fn merge_keys_and_aliases() {
+        let config_source = YamlConfigSource::from_str(MERGE_YAML).unwrap();
+
+        let cases = [
+            ("environments.dev.host", Some("localhost")),
+            // Nested merge of base into defaults, overridden by defaults
+            ("environments.dev.retries", Some("5")),
+            ("environments.dev.timeout", Some("30")),
+            ("environments.dev.pool.size", Some("5")),
+            // Explicit keys override merged keys
+            ("environments.prod.host", Some("db.prod.internal")),
+            ("environments.prod.pool.size", Some("5")),
+            ("environments.prod.pool.max", Some("50")),
+            // Earlier mappings in a merge sequence take precedence
+            ("environments.prod.timeout", Some("10")),
+            ("environments.prod.level", Some("info")),
+            ("environments.prod.retries", Some("5")),
+            ("environments.dev.<<", None),
+            ("replicas[0].host", Some("localhost")),
+            ("replicas[0].timeout", Some("30")),
+            ("replicas[1].host", Some("replica.internal")),
+            ("replicas[1].pool.size", Some("5")),
+            ("exposed[1]", Some("443")),
+        ];
+        for (property_name, expected) in cases {
+            assert_eq!(
+                config_source.get_value(property_name),
+                expected.map(|value| value.to_string()),
+                "{}",
+                property_name
+            );
+        }
+    }
+
+    #[test]
+    fn merge_key_with_scalar_value_is_a_plain_key() {
+        let config_source = YamlConfigSource::from_str("shift:\n  <<: left\n").unwrap();
+        assert_eq!(
+            config_source.get_value("shift.<<"),
+            Some("left".to_string())
+        );
+    }
+
+    #[test]
+    fn include_tags_and_includes_list() {
+        let config_source = YamlConfigSource::from_file("test_configs/includes/app.yaml").unwrap();
+
+        let cases = [
+            ("app.name", Some("demo")),
+            ("app.limits.requests", Some("100")),
+            // Nested includes resolve relative to the including file
+            ("app.limits.timeouts.read", Some("30")),
+            ("database.host", Some("db.internal")),
+            // The including document overrides what it includes
+            ("database.pool", Some("20")),
+            ("queues.default", Some("jobs")),
+            ("includes", None),
+        ];
+        for (property_name, expected) in cases {
+            assert_eq!(
+                config_source.get_value(property_name),
+                expected.map(|value| value.to_string()),
+                "{}",
+                property_name
+            );
+        }
+    }
+
+    fn include_error(file_path: &str) -> IncludeError {
+        match YamlConfigSource::from_file(file_path).err() {
+            Some(FileError::YamlParseError {
+                error: YamlParseError::IncludeError(error),
+                ..
+            }) => error,
+            _ => panic!("expected an include error"),
+        }
+    }
+
+    #[test]
+    fn include_cycles_are_detected() {
+        let error = include_error("test_configs/includes/cycle/a.yaml");
+
+        assert_eq!(error.kind, IncludeErrorKind::Cycle);
+        assert_eq!(
+            error.include_chain,
+            vec![
+                "test_configs/includes/cycle/a.yaml",
+                "test_configs/includes/cycle/b.yaml",
+                "test_configs/includes/cycle/a.yaml",
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_includes_report_the_include_chain() {
+        let error = include_error("test_configs/includes/missing.yaml");
+
+        assert_eq!(error.kind, IncludeErrorKind::Io(io::ErrorKind::NotFound));
+        assert_eq!(
+            error.include_chain,
+            vec![
+                "test_configs/includes/missing.yaml",
+                "test_configs/includes/missing-nested.yaml",
+                "test_configs/includes/does-not-exist.yaml",
+            ]
+        );
+        assert!(error
+            .to_string()
+            .ends_with("(include chain: test_configs/includes/missing.yaml -> test_configs/includes/missing-nested.yaml -> test_configs/includes/does-not-exist.yaml)"));
+    }
+
+    #[test]
+    fn includes_list_must_hold_paths() {
+        let result = YamlConfigSource::from_str("includes: database.yaml\n");
+        assert!(matches!(
+            result.err(),
+            Some(YamlParseError::IncludeError(IncludeError {
+                kind: IncludeErrorKind::InvalidIncludesList,
+                ..
+            }))
+        ));
+    }
+
+    #[test]
+    fn strict_mode_accepts_include_tags() {
+        let result = YamlConfigSource::from_file_with_options(
+            "test_configs/includes/app.yaml",
+            &YamlParseOptions {
+                strict: true,
+                ..Default::default()
+            },
+        );
+        assert!(result.is_ok());
+    }
+
     #[test]
     fn parse_multi_stanza_yaml_string() {
         let yaml_str = "
@@ -138,8 +1174,8 @@ mod tests {
 
         let config_source = config_result.unwrap();
 
-        assert!(!config_source.yaml_doc["database"].is_null());
-        assert!(!config_source.yaml_doc["endpoints"].is_null());
+        assert!(!config_source.properties_with_prefix("database").is_empty());
+        assert!(!config_source.properties_with_prefix("endpoints").is_empty());
     }
 
     #[test]
@@ -152,8 +1188,126 @@ mod tests {
             health: '/health'
         ";
 
-        let config_result = YamlConfigSource::from_str(yaml_str);
-        assert!(config_result.is_err());
+        let config_source = YamlConfigSource::from_str(yaml_str).unwrap();
+        assert_eq!(
+            config_source.get_value("database.user"),
+            Some("foo".to_string())
+        );
+        assert_eq!(
+            config_source.get_value("endpoints.health"),
+            Some("/health".to_string())
+        );
+    }
+
+    const PROFILE_YAML: &str = "
+database:
+  host: localhost
+  user: dev
+  pool:
+    size: 5
+---
+configler:
+  profile: prod
+database:
+  host: db.prod.internal
+  pool:
+    size: 50
+---
+database:
+  user: base-override
+---
+configler.profile: staging, prod
+database:
+  user: shared-deploy-user
+---
+configler:
+  profile: [qa]
+database:
+  host: db.qa.internal
+";
+
+    fn parse_with_profile(profile: Option<&str>) -> YamlConfigSource {
+        YamlConfigSource::from_str_with_options(
+            PROFILE_YAML,
+            &YamlParseOptions {
+                profile: profile.map(|profile| profile.to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn untagged_documents_form_the_base() {
+        let config_source = parse_with_profile(None);
+
+        assert_eq!(
+            config_source.get_value("database.host"),
+            Some("localhost".to_string())
+        );
+        assert_eq!(
+            config_source.get_value("database.user"),
+            Some("base-override".to_string())
+        );
+        assert_eq!(
+            config_source.get_value("database.pool.size"),
+            Some("5".to_string())
+        );
+    }
+
+    #[test]
+    fn active_profile_documents_override_the_base() {
+        let config_source = parse_with_profile(Some("prod"));
+
+        assert_eq!(
+            config_source.get_value("database.host"),
+            Some("db.prod.internal".to_string())
+        );
+        assert_eq!(
+            config_source.get_value("database.user"),
+            Some("shared-deploy-user".to_string())
+        );
+        assert_eq!(
+            config_source.get_value("database.pool.size"),
+            Some("50".to_string())
+        );
+
+        let config_source = parse_with_profile(Some("staging"));
+        assert_eq!(
+            config_source.get_value("database.host"),
+            Some("localhost".to_string())
+        );
+        assert_eq!(
+            config_source.get_value("database.user"),
+            Some("shared-deploy-user".to_string())
+        );
+
+        let config_source = parse_with_profile(Some("qa"));
+        assert_eq!(
+            config_source.get_value("database.host"),
+            Some("db.qa.internal".to_string())
+        );
+    }
+
+    #[test]
+    fn empty_documents_are_ignored() {
+        let yaml_str = "
+database:
+  user: foo
+---
+---
+";
+        let config_source = YamlConfigSource::from_str(yaml_str).unwrap();
+        assert_eq!(
+            config_source.get_value("database.user"),
+            Some("foo".to_string())
+        );
+    }
+
+    #[test]
+    fn empty_yaml_string() {
+        let config_source = YamlConfigSource::from_str("").unwrap();
+        assert_eq!(config_source.get_value("database.user"), None);
     }
 
     #[test]
@@ -174,8 +1328,8 @@ mod tests {
         assert!(config_result.is_ok());
 
         let config = config_result.unwrap();
-        assert!(!config.yaml_doc["database"].is_null());
-        assert!(!config.yaml_doc["app"].is_null());
+        assert!(!config.properties_with_prefix("database").is_empty());
+        assert!(!config.properties_with_prefix("some-app-config").is_empty());
     }
 
     #[test]
@@ -184,6 +1338,176 @@ mod tests {
         assert!(config_result.is_err());
     }
 
+    #[test]
+    fn get_non_string_scalars() {
+        let yaml_str = "
+        feature:
+            enabled: true
+            disabled: false
+        server:
+            port: 8080
+            offset: -42
+            hex: 0x1F
+        ratio:
+            half: 0.50
+            scientific: 1.5e3
+            infinite: .inf
+        quoted: 'true'
+        nothing: ~
+        explicit_null: null
+        list:
+            - one
+        ";
+        let config_source = YamlConfigSource::from_str(yaml_str).unwrap();
+
+        let cases = [
+            ("feature.enabled", Some("true")),
+            ("feature.disabled", Some("false")),
+            ("server.port", Some("8080")),
+            ("server.offset", Some("-42")),
+            ("server.hex", Some("31")),
+            ("ratio.half", Some("0.5")),
+            ("ratio.scientific", Some("1500.0")),
+            ("ratio.infinite", Some("inf")),
+            ("quoted", Some("true")),
+            ("nothing", Some("")),
+            ("explicit_null", Some("")),
+            ("missing", None),
+            ("nothing.deeper", None),
+            ("server", None),
+            ("list", None),
+        ];
+        for (property_name, expected) in cases {
+            assert_eq!(
+                config_source.get_value(property_name),
+                expected.map(|value| value.to_string()),
+                "{}",
+                property_name
+            );
+        }
+    }
+
+    #[test]
+    fn get_quoted_and_indexed_values() {
+        let yaml_str = "
+        labels:
+            kubernetes.io/name: configler
+            app: demo
+        servers:
+            - host: first.internal
+              port: 80
+            - host: second.internal
+              ports: [8080, 8443]
+        ";
+        let config_source = YamlConfigSource::from_str(yaml_str).unwrap();
+
+        let cases = [
+            ("labels.\"kubernetes.io/name\"", Some("configler")),
+            ("labels.\"app\"", Some("demo")),
+            ("labels.kubernetes.io/name", None),
+            ("servers[0].host", Some("first.internal")),
+            ("servers[1].host", Some("second.internal")),
+            ("servers[1].ports[1]", Some("8443")),
+            ("servers[2].host", None),
+            ("labels[0]", None),
+            ("servers[one].host", None),
+        ];
+        for (property_name, expected) in cases {
+            assert_eq!(
+                config_source.get_value(property_name),
+                expected.map(|value| value.to_string()),
+                "{}",
+                property_name
+            );
+        }
+    }
+
+    #[test]
+    fn enumerate_flattened_properties() {
+        let yaml_str = "
+        database:
+            user: foo
+            pool:
+                size: 5
+        databases:
+            primary: db1
+        labels:
+            kubernetes.io/name: configler
+        servers:
+            - host: first.internal
+            - [80, 443]
+        empty: {}
+        ";
+        let config_source = YamlConfigSource::from_str(yaml_str).unwrap();
+
+        assert_eq!(
+            config_source.property_names(),
+            vec![
+                "database.pool.size",
+                "database.user",
+                "databases.primary",
+                "labels.\"kubernetes.io/name\"",
+                "servers[0].host",
+                "servers[1][0]",
+                "servers[1][1]",
+            ]
+        );
+        assert_eq!(
+            config_source.properties_with_prefix("database"),
+            vec![("database.pool.size", "5"), ("database.user", "foo")]
+        );
+        assert_eq!(
+            config_source.properties_with_prefix("\"servers\"[1]"),
+            vec![("servers[1][0]", "80"), ("servers[1][1]", "443")]
+        );
+        assert_eq!(
+            config_source.properties_with_prefix("database.user"),
+            vec![]
+        );
+        assert_eq!(
+            config_source.properties_with_prefix("invalid..prefix"),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn config_ordinal_sets_the_ordinal() {
+        let config_source = YamlConfigSource::from_str("config_ordinal: 120\n").unwrap();
+        assert_eq!(config_source.get_ordinal(), 120);
+        assert_eq!(
+            config_source
+                .clone()
+                .with_default_ordinal(265)
+                .get_ordinal(),
+            120
+        );
+        assert_eq!(config_source.with_ordinal(265).get_ordinal(), 265);
+
+        let default_source = YamlConfigSource::from_str("database:\n  user: foo\n").unwrap();
+        assert_eq!(default_source.with_default_ordinal(250).get_ordinal(), 250);
+
+        assert_eq!(
+            YamlConfigSource::from_str("config_ordinal: high\n").err(),
+            Some(YamlParseError::InvalidOrdinal("high".to_string()))
+        );
+    }
+
+    #[test]
+    fn clones_share_the_index() {
+        let config_source = YamlConfigSource::from_str("database:\n  user: foo\n").unwrap();
+        let cloned_source = config_source.clone();
+        assert!(Arc::ptr_eq(&config_source.index, &cloned_source.index));
+    }
+
+    #[test]
+    fn get_yaml_file_boolean() {
+        let config_source = YamlConfigSource::from_file("./test_configs/config.yaml").unwrap();
+        assert_eq!(
+            config_source.get_value("some-app-config.is_feature_on"),
+            Some("true".to_string())
+        );
+    }
+
     #[test]
     fn get_yaml_value() {
         let yaml_str = "
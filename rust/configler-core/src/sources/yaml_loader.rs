@@ -0,0 +1,163 @@
+use std::{collections::HashMap, mem};
+
+use yaml_rust2::{
+    parser::{MarkedEventReceiver, Parser, Tag},
+    scanner::{Marker, TScalarStyle},
+    Event, ScanError, Yaml,
+};
+
+use super::yaml::YamlParseError;
+
+/// Handle and suffix of the tag replacing a file path with the contents of that file
+pub(super) const INCLUDE_TAG: (&str, &str) = ("!", "include");
+
+pub(super) type ResolveInclude<'a> = dyn FnMut(&str) -> Result<Yaml, YamlParseError> + 'a;
+
+/// Loads every document in `yaml_str` the same way `YamlLoader` does, except that
+/// scalars tagged `!include` are replaced by the node returned from `resolve_include`
+pub(super) fn load_documents(
+    yaml_str: &str,
+    resolve_include: &mut ResolveInclude,
+) -> Result<Vec<Yaml>, YamlParseError> {
+    let mut builder = DocumentBuilder {
+        docs: Vec::new(),
+        node_stack: Vec::new(),
+        key_stack: Vec::new(),
+        anchors: HashMap::new(),
+        resolve_include,
+        error: None,
+    };
+    Parser::new_from_str(yaml_str)
+        .load(&mut builder, true)
+        .map_err(YamlParseError::ScanError)?;
+
+    match builder.error {
+        Some(error) => Err(error),
+        None => Ok(builder.docs),
+    }
+}
+
+struct DocumentBuilder<'a, 'b> {
+    docs: Vec<Yaml>,
+    /// Open nodes with their anchor id, innermost last
+    node_stack: Vec<(Yaml, usize)>,
+    /// Key of each open mapping, `BadValue` while the key itself is being read
+    key_stack: Vec<Yaml>,
+    anchors: HashMap<usize, Yaml>,
+    resolve_include: &'a mut ResolveInclude<'b>,
+    error: Option<YamlParseError>,
+}
+
+impl MarkedEventReceiver for DocumentBuilder<'_, '_> {
+    fn on_event(&mut self, event: Event, mark: Marker) {
+        if self.error.is_some() {
+            return;
+        }
+        if let Err(error) = self.handle_event(event, mark) {
+            self.error = Some(error);
+        }
+    }
+}
+
+impl DocumentBuilder<'_, '_> {
+    fn handle_event(&mut self, event: Event, mark: Marker) -> Result<(), YamlParseError> {
+        match event {
+            Event::DocumentEnd => {
+                let doc = self.node_stack.pop().map_or(Yaml::BadValue, |(doc, _)| doc);
+                self.docs.push(doc);
+            }
+            Event::SequenceStart(anchor, _) => {
+                self.node_stack.push((Yaml::Array(Vec::new()), anchor))
+            }
+            Event::MappingStart(anchor, _) => {
+                self.node_stack
+                    .push((Yaml::Hash(Default::default()), anchor));
+                self.key_stack.push(Yaml::BadValue);
+            }
+            Event::SequenceEnd => {
+                let node = self.node_stack.pop().unwrap();
+                self.insert_node(node, mark)?;
+            }
+            Event::MappingEnd => {
+                self.key_stack.pop();
+                let node = self.node_stack.pop().unwrap();
+                self.insert_node(node, mark)?;
+            }
+            Event::Scalar(value, style, anchor, tag) => {
+                let node = match tag {
+                    Some(tag) if (tag.handle.as_str(), tag.suffix.as_str()) == INCLUDE_TAG => {
+                        (self.resolve_include)(&value)?
+                    }
+                    tag => scalar_node(value, style, tag),
+                };
+                self.insert_node((node, anchor), mark)?;
+            }
+            Event::Alias(anchor) => {
+                let node = self.anchors.get(&anchor).cloned().unwrap_or(Yaml::BadValue);
+                self.insert_node((node, 0), mark)?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn insert_node(
+        &mut self,
+        (node, anchor): (Yaml, usize),
+        mark: Marker,
+    ) -> Result<(), YamlParseError> {
+        // Anchor ids start from 1
+        if anchor > 0 {
+            self.anchors.insert(anchor, node.clone());
+        }
+        if self.node_stack.is_empty() {
+            self.node_stack.push((node, anchor));
+            return Ok(());
+        }
+
+        match self.node_stack.last_mut() {
+            Some((Yaml::Array(items), _)) => items.push(node),
+            Some((Yaml::Hash(hash), _)) => {
+                let key = self.key_stack.last_mut().unwrap();
+                if key.is_badvalue() {
+                    *key = node;
+                } else {
+                    let key = mem::replace(key, Yaml::BadValue);
+                    if hash.contains_key(&key) {
+                        return Err(YamlParseError::ScanError(ScanError::new_string(
+                            mark,
+                            format!("{:?}: duplicated key in mapping", key),
+                        )));
+                    }
+                    hash.insert(key, node);
+                }
+            }
+            _ => unreachable!("only collections are kept open"),
+        }
+        Ok(())
+    }
+}
+
+/// Converts a scalar into a node, honoring the core schema tags
+fn scalar_node(value: String, style: TScalarStyle, tag: Option<Tag>) -> Yaml {
+    if style != TScalarStyle::Plain {
+        return Yaml::String(value);
+    }
+    match tag {
+        None => Yaml::from_str(&value),
+        Some(tag) if tag.handle == "tag:yaml.org,2002:" => match tag.suffix.as_str() {
+            "bool" => value.parse().map_or(Yaml::BadValue, Yaml::Boolean),
+            "int" => value.parse().map_or(Yaml::BadValue, Yaml::Integer),
+            "float" => match Yaml::from_str(&value) {
+                Yaml::Real(_) | Yaml::Integer(_) => Yaml::Real(value),
+                _ => Yaml::BadValue,
+            },
+            "null" => match value.as_str() {
+                "~" | "null" => Yaml::Null,
+                _ => Yaml::BadValue,
+            },
+            _ => Yaml::String(value),
+        },
+        Some(_) => Yaml::String(value),
+    }
+}
@@ -0,0 +1,77 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    str::FromStr,
+};
+
+use configler_core::{
+    sources::{dot_env::DotEnvironmentConfigSource, YamlConfigSource},
+    ConfigBuilder,
+};
+
+/// Counts the allocations of each thread so the test harness does not skew the counts
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|allocations| allocations.set(allocations.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn count_allocations(run: impl Fn()) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    run();
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+#[test]
+fn verify_borrowed_lookups_do_not_allocate() {
+    let yaml_source =
+        YamlConfigSource::from_str("database:\n  user: foo\n  password: bar\n").unwrap();
+    let dot_env_source =
+        DotEnvironmentConfigSource::from_str("SERVER_PORT=8080\nFEATURE_FLAG=on\n").unwrap();
+    let config = ConfigBuilder::new()
+        .add_custom_source(Box::new(dot_env_source))
+        .add_custom_source(Box::new(yaml_source))
+        .build()
+        .unwrap();
+    let property_names = ["database.user", "server.port", "missing.value"];
+    let read_all = |read: &dyn Fn(&str)| {
+        for property_name in property_names {
+            read(property_name);
+        }
+    };
+
+    // The first lookups fill the environment name cache
+    read_all(&|property_name| {
+        config.get_value_ref(property_name);
+    });
+
+    assert_eq!(
+        count_allocations(|| read_all(&|property_name| {
+            assert_eq!(
+                config.get_value_ref(property_name).is_some(),
+                property_name != "missing.value"
+            );
+        })),
+        0
+    );
+    assert_eq!(
+        count_allocations(|| read_all(&|property_name| {
+            config.get_value(property_name);
+        })),
+        2
+    );
+}
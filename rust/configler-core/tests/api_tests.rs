@@ -1,4 +1,9 @@
-use std::{collections::HashMap, str::FromStr};
+use std::{
+    collections::HashMap,
+    str::FromStr,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    thread,
+};
 
 use configler_core::{
     self,
@@ -6,8 +11,16 @@ use configler_core::{
     Config, ConfigBuilder, ConfigPropertyGroup, ConfigValueError, SourceName,
 };
 
+/// Serializes tests that set bootstrap variables or build lazy sources reading them,
+/// the process environment is shared by every test in this binary
+fn lock_environment() -> MutexGuard<'static, ()> {
+    static ENVIRONMENT: Mutex<()> = Mutex::new(());
+    ENVIRONMENT.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
 #[test]
 fn verify_lazy_builder_and_config_visibility() {
+    let _environment = lock_environment();
     let builder_result = ConfigBuilder::new()
         .add_source(SourceName::Environment)
         .add_source(SourceName::YamlFile)
@@ -82,6 +95,27 @@ fn verify_custom_source_works_with_builder() {
     );
 }
 
+#[test]
+fn verify_config_is_shareable_between_threads() {
+    let yaml_source = YamlConfigSource::from_str("database:\n  user: baz\n").unwrap();
+    let config = Arc::new(
+        ConfigBuilder::new()
+            .add_custom_source(Box::new(yaml_source))
+            .build()
+            .unwrap(),
+    );
+
+    let workers: Vec<_> = (0..4)
+        .map(|_| {
+            let config = Arc::clone(&config);
+            thread::spawn(move || config.get_value("database.user"))
+        })
+        .collect();
+    for worker in workers {
+        assert_eq!(worker.join().unwrap(), Some("baz".to_string()));
+    }
+}
+
 #[test]
 fn verify_config_property_group_pattern() {
     let yaml_source = YamlConfigSource::from_str(
@@ -103,17 +137,11 @@ fn verify_config_property_group_pattern() {
     }
     impl<'a> DbConfig<'a> {
         fn get_username(&self) -> Result<String, ConfigValueError> {
-            match self.config.get_value("database.user") {
-                Some(value) => Ok(value),
-                None => Err(ConfigValueError::NullError),
-            }
+            self.config.get_required_value("database.user")
         }
 
         fn get_password(&self) -> Result<String, ConfigValueError> {
-            match self.config.get_value("database.password") {
-                Some(value) => Ok(value),
-                None => Err(ConfigValueError::NullError),
-            }
+            self.config.get_required_value("database.password")
         }
     }
     impl<'a> ConfigPropertyGroup<'a> for DbConfig<'a> {
@@ -157,3 +185,84 @@ fn verify_config_property_group_pattern() {
         Some(&Some("foo".to_string()))
     );
 }
+
+#[test]
+fn verify_locations_bootstrap_variable() {
+    let _environment = lock_environment();
+    std::env::set_var(
+        "CONFIGLER_LOCATIONS",
+        "./test_configs/site, ./test_configs/does-not-exist, ./test_configs",
+    );
+
+    let builder_result = ConfigBuilder::new()
+        .add_source(SourceName::YamlFile)
+        .set_config_directory("./test_configs/does-not-exist")
+        .build();
+
+    std::env::remove_var("CONFIGLER_LOCATIONS");
+
+    assert!(builder_result.is_ok());
+    let config = builder_result.unwrap();
+    assert_eq!(
+        config.get_value("database.user"),
+        Some("site-user".to_string())
+    );
+    assert_eq!(
+        config.get_value("database.password"),
+        Some("fake".to_string())
+    );
+}
+
+#[test]
+fn verify_profile_bootstrap_variable() {
+    let _environment = lock_environment();
+    std::env::set_var("CONFIGLER_PROFILE", "production");
+
+    let builder_result = ConfigBuilder::new()
+        .add_source(SourceName::DotEnvironmentFile)
+        .set_config_directory("./test_configs/cascade")
+        .set_profile("staging")
+        .build();
+
+    std::env::remove_var("CONFIGLER_PROFILE");
+
+    assert!(builder_result.is_ok());
+    let config = builder_result.unwrap();
+    assert_eq!(
+        config.get_value("cascade.d"),
+        Some("production-local".to_string())
+    );
+}
+
+#[test]
+fn verify_global_config_outlives_its_callers() {
+    struct UserConfig<'a> {
+        config: &'a Config,
+    }
+    impl<'a> ConfigPropertyGroup<'a> for UserConfig<'a> {
+        fn get_value_map(&self) -> Result<HashMap<String, Option<String>>, ConfigValueError> {
+            let user = self.config.get_required_value("global.user")?;
+            Ok(HashMap::from([("GLOBAL_USER".to_string(), Some(user))]))
+        }
+
+        fn from_config(config: &'a Config) -> Self {
+            UserConfig { config }
+        }
+    }
+
+    // Property groups built from the global config can be returned and stored freely
+    fn user_config() -> UserConfig<'static> {
+        UserConfig::from_config(configler_core::config().unwrap())
+    }
+
+    let yaml_source = YamlConfigSource::from_str("global:\n  user: baz\n").unwrap();
+    let mut builder = ConfigBuilder::new();
+    builder.add_custom_source(Box::new(yaml_source));
+    configler_core::init(&builder).unwrap();
+
+    let stored = thread::spawn(user_config).join().unwrap();
+    assert_eq!(
+        stored.get_value_map().unwrap().get("GLOBAL_USER"),
+        Some(&Some("baz".to_string()))
+    );
+}